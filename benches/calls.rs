@@ -0,0 +1,92 @@
+//! compares the checked call path with `#[fast]` and `calls fast;`.
+//!
+//! run with `cargo bench`, requires a JDK to be installed.
+use std::hint::black_box;
+use std::time::Instant;
+
+use jni::{InitArgsBuilder, JavaVM};
+use jni_bind::{import_class, jint};
+
+const ITERATIONS: u32 = 1_000_000;
+
+import_class! {
+    "java/lang/Object";
+    Object;
+    constructor();
+    fn hashCode(&self) -> jint;
+}
+
+import_class! {
+    "java/lang/Object";
+    FastObject;
+    constructor();
+    #[fast]
+    fn hashCode(&self) -> jint;
+}
+
+import_class! {
+    "java/lang/Integer";
+    Integer;
+    static fn valueOf(i: jint) -> Integer;
+    fn intValue(&self) -> jint;
+}
+
+import_class! {
+    "java/lang/Integer";
+    FastInteger;
+    calls fast;
+    static fn valueOf(i: jint) -> FastInteger;
+    fn intValue(&self) -> jint;
+}
+
+fn bench(name: &str, mut f: impl FnMut()) {
+    // warm up the caches and the jit
+    for _ in 0..ITERATIONS / 10 {
+        f();
+    }
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{:<28} {:>8.1} ns/call",
+        name,
+        elapsed.as_nanos() as f64 / ITERATIONS as f64
+    );
+}
+
+fn main() {
+    let args = InitArgsBuilder::new().build().unwrap();
+    let vm = JavaVM::new(args).unwrap();
+    let mut env = vm.attach_current_thread().unwrap();
+
+    let obj = Object::new(&mut env).unwrap();
+    let fast_obj = FastObject::new(&mut env).unwrap();
+
+    bench("hashCode", || {
+        black_box(obj.hash_code(&mut env).unwrap());
+    });
+    bench("hashCode #[fast]", || {
+        black_box(fast_obj.hash_code(&mut env).unwrap());
+    });
+
+    let int = Integer::value_of(&mut env, 42).unwrap();
+    let fast_int = FastInteger::value_of(&mut env, 42).unwrap();
+
+    bench("intValue", || {
+        black_box(int.int_value(&mut env).unwrap());
+    });
+    bench("intValue calls fast", || {
+        black_box(fast_int.int_value(&mut env).unwrap());
+    });
+
+    bench("valueOf", || {
+        black_box(Integer::value_of(&mut env, 42).unwrap());
+    });
+    bench("valueOf calls fast", || {
+        black_box(FastInteger::value_of(&mut env, 42).unwrap());
+    });
+}
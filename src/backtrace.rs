@@ -0,0 +1,318 @@
+use core::fmt;
+use std::backtrace::{Backtrace, BacktraceStatus};
+
+use jni::objects::{JObject, JValue};
+use jni::JNIEnv;
+
+use crate::exception::catch;
+use crate::throwable::stack_trace;
+use crate::{Error, StackTraceElement};
+
+/// the frames of a rust backtrace, as stack trace elements named `module::path.function`.
+///
+/// the frames of the backtrace itself and of this crate at the top of the stack are skipped,
+/// and an uncaptured backtrace has no frames.
+pub(crate) fn rust_frames(backtrace: &Backtrace) -> Vec<StackTraceElement> {
+    if backtrace.status() != BacktraceStatus::Captured {
+        return Vec::new();
+    }
+
+    parse_frames(&backtrace.to_string())
+}
+
+/// parses the frames of a backtrace printed in the default format of std.
+fn parse_frames(backtrace: &str) -> Vec<StackTraceElement> {
+    // std does not expose the frames yet, so parse the default format:
+    // `  12: path::to::function` followed by `at src/file.rs:10:5` lines
+    let mut frames = Vec::new();
+
+    for line in backtrace.lines().map(str::trim) {
+        if let Some(location) = line.strip_prefix("at ") {
+            if let Some(frame) = frames.last_mut() {
+                set_location(frame, location);
+            }
+            continue;
+        }
+
+        let Some((index, symbol)) = line.split_once(": ") else {
+            continue;
+        };
+        if index.is_empty() || !index.bytes().all(|b| b.is_ascii_digit()) {
+            continue;
+        }
+
+        let (class_name, method_name) = split_symbol(symbol);
+        frames.push(StackTraceElement {
+            class_name: class_name.to_owned(),
+            method_name: method_name.to_owned(),
+            file_name: None,
+            line_number: None,
+            native: false,
+        });
+    }
+
+    let internal = frames
+        .iter()
+        .take_while(|f| {
+            f.class_name.starts_with("std::backtrace")
+                || f.class_name.contains("jni_bind::")
+                || f.class_name.starts_with("core::result::")
+        })
+        .count();
+    frames.drain(..internal);
+
+    frames
+}
+
+/// splits `path::to::function` into `path::to` and `function`, ignoring `::` inside `<...>`.
+fn split_symbol(symbol: &str) -> (&str, &str) {
+    let mut depth = 0i32;
+    let mut split = None;
+    let bytes = symbol.as_bytes();
+
+    for i in 0..bytes.len() {
+        match bytes[i] {
+            b'<' => depth += 1,
+            b'>' => depth -= 1,
+            b':' if depth == 0 && bytes.get(i + 1) == Some(&b':') && (i == 0 || bytes[i - 1] != b':') => {
+                split = Some(i)
+            }
+            _ => {}
+        }
+    }
+
+    match split {
+        Some(i) => (&symbol[..i], &symbol[i + 2..]),
+        None => ("", symbol),
+    }
+}
+
+/// sets the file and line of `frame` from `file:line:column`, only the first location of an inlined frame is kept.
+fn set_location(frame: &mut StackTraceElement, location: &str) {
+    if frame.file_name.is_some() {
+        return;
+    }
+
+    let mut parts = location.rsplitn(3, ':');
+    let (column, line, file) = (parts.next(), parts.next(), parts.next());
+
+    match (file, line.and_then(|l| l.parse().ok()), column) {
+        (Some(file), Some(line), Some(_)) => {
+            frame.file_name = Some(file.to_owned());
+            frame.line_number = Some(line);
+        }
+        _ => frame.file_name = Some(location.to_owned()),
+    }
+}
+
+/// the stack of a java exception interleaved with the rust backtrace of the call that captured it.
+///
+/// the rust frames are placed before the first native java frame, which is where java called into rust,
+/// or after the java frames if there is none.
+pub struct CombinedBacktrace<'a> {
+    pub(crate) java: &'a [StackTraceElement],
+    pub(crate) rust: Vec<StackTraceElement>,
+}
+
+impl CombinedBacktrace<'_> {
+    /// the frames from the top of the stack, and whether each of them is a java frame.
+    pub fn frames(&self) -> impl Iterator<Item = (&StackTraceElement, bool)> {
+        let boundary = self.java.iter().position(|f| f.native).unwrap_or(self.java.len());
+        let (above, below) = self.java.split_at(boundary);
+
+        above
+            .iter()
+            .map(|f| (f, true))
+            .chain(self.rust.iter().map(|f| (f, false)))
+            .chain(below.iter().map(|f| (f, true)))
+    }
+}
+
+impl fmt::Display for CombinedBacktrace<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (frame, java)) in self.frames().enumerate() {
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+
+            write!(f, "\tat {}{}", frame, if java { "" } else { " [rust]" })?;
+        }
+
+        Ok(())
+    }
+}
+
+/// prepends the frames of `backtrace` to the stack trace of `throwable`, as synthetic java stack trace elements.
+pub(crate) fn push_rust_frames(env: &mut JNIEnv, throwable: &JObject, backtrace: &Backtrace) -> Result<(), Error> {
+    let rust = rust_frames(backtrace);
+    if rust.is_empty() {
+        return Ok(());
+    }
+
+    let java = stack_trace(env, throwable)?;
+
+    env.with_local_frame(8, |env| -> Result<(), Error> {
+        let class = env.find_class("java/lang/StackTraceElement").map_err(|e| catch(env, e))?;
+        let array = env.new_object_array((rust.len() + java.len()) as i32, &class, JObject::null())?;
+
+        for (i, frame) in rust.iter().chain(java.iter()).enumerate() {
+            let element = new_element(env, frame)?;
+            env.set_object_array_element(&array, i as i32, &element)?;
+            env.delete_local_ref(element)?;
+        }
+
+        env.call_method(
+            throwable,
+            "setStackTrace",
+            "([Ljava/lang/StackTraceElement;)V",
+            &[JValue::Object(&array)],
+        )
+        .map_err(|e| catch(env, e))?;
+
+        Ok(())
+    })
+}
+
+/// a `java.lang.StackTraceElement` for `frame`.
+fn new_element<'local>(env: &mut JNIEnv<'local>, frame: &StackTraceElement) -> Result<JObject<'local>, Error> {
+    let class_name = env.new_string(&frame.class_name)?;
+    let method_name = env.new_string(&frame.method_name)?;
+    let file_name = match &frame.file_name {
+        Some(file) => JObject::from(env.new_string(file)?),
+        None => JObject::null(),
+    };
+    let line = match (frame.native, frame.line_number) {
+        (true, _) => -2,
+        (false, Some(line)) => line as i32,
+        (false, None) => -1,
+    };
+
+    let element = env
+        .new_object(
+            "java/lang/StackTraceElement",
+            "(Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;I)V",
+            &[
+                JValue::Object(&class_name),
+                JValue::Object(&method_name),
+                JValue::Object(&file_name),
+                JValue::Int(line),
+            ],
+        )
+        .map_err(|e| catch(env, e));
+
+    env.delete_local_ref(class_name)?;
+    env.delete_local_ref(method_name)?;
+    env.delete_local_ref(file_name)?;
+
+    element
+}
+
+/// interleaves the java stack trace `java` with the frames of `rust`.
+pub(crate) fn combine<'a>(java: &'a [StackTraceElement], rust: &Backtrace) -> CombinedBacktrace<'a> {
+    CombinedBacktrace {
+        java,
+        rust: rust_frames(rust),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BACKTRACE: &str = "   0: std::backtrace_rs::backtrace::libunwind::trace
+             at /rustc/1a2b3c/library/std/src/../../backtrace/src/backtrace/libunwind.rs:116:5
+   1: std::backtrace::Backtrace::create
+             at /rustc/1a2b3c/library/std/src/backtrace.rs:331:13
+   2: jni_bind::throw::new_exception
+             at ./src/throw.rs:150:5
+   3: <jni_bind::Error as jni_bind::throw::IntoJavaException>::into_java_exception
+             at ./src/throw.rs:90:9
+   4: core::result::Result<T,E>::map_err
+             at /rustc/1a2b3c/library/core/src/result.rs:829:27
+   5: app::native::{{closure}}
+             at ./src/native.rs:12:9
+   6: jni_bind::vm::with_attached_env
+             at ./src/vm.rs:80:5
+   7: <T as core::fmt::Display>::fmt
+             at ./src/display.rs:40:5
+      app::display::write
+             at ./src/display.rs:7:13
+   8: main
+";
+
+    fn frame(class_name: &str, method_name: &str, file_name: Option<&str>, line_number: Option<u32>) -> StackTraceElement {
+        StackTraceElement {
+            class_name: class_name.to_owned(),
+            method_name: method_name.to_owned(),
+            file_name: file_name.map(str::to_owned),
+            line_number,
+            native: false,
+        }
+    }
+
+    #[test]
+    fn parse_frames_skips_the_top_internal_frames() {
+        assert_eq!(
+            parse_frames(BACKTRACE),
+            vec![
+                frame("app::native", "{{closure}}", Some("./src/native.rs"), Some(12)),
+                frame("jni_bind::vm", "with_attached_env", Some("./src/vm.rs"), Some(80)),
+                frame("<T as core::fmt::Display>", "fmt", Some("./src/display.rs"), Some(40)),
+                frame("", "main", None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn uncaptured_backtraces_have_no_frames() {
+        assert!(rust_frames(&Backtrace::disabled()).is_empty());
+    }
+
+    #[test]
+    fn split_symbol_splits_at_the_last_path_separator() {
+        assert_eq!(split_symbol("path::to::function"), ("path::to", "function"));
+        assert_eq!(split_symbol("main"), ("", "main"));
+        assert_eq!(split_symbol("app::native::{{closure}}"), ("app::native", "{{closure}}"));
+    }
+
+    #[test]
+    fn split_symbol_ignores_separators_in_generics() {
+        assert_eq!(
+            split_symbol("<T as core::fmt::Display>::fmt"),
+            ("<T as core::fmt::Display>", "fmt")
+        );
+        assert_eq!(
+            split_symbol("<alloc::vec::Vec<T> as core::ops::drop::Drop>::drop"),
+            ("<alloc::vec::Vec<T> as core::ops::drop::Drop>", "drop")
+        );
+        assert_eq!(split_symbol("<app::Parser as core::str::FromStr>"), ("", "<app::Parser as core::str::FromStr>"));
+    }
+
+    #[test]
+    fn set_location_parses_file_and_line() {
+        let mut f = frame("app", "main", None, None);
+        set_location(&mut f, "C:\\app\\src\\main.rs:10:5");
+
+        assert_eq!(f.file_name.as_deref(), Some("C:\\app\\src\\main.rs"));
+        assert_eq!(f.line_number, Some(10));
+    }
+
+    #[test]
+    fn set_location_keeps_unparsed_locations_as_the_file() {
+        let mut f = frame("app", "main", None, None);
+        set_location(&mut f, "src/main.rs");
+
+        assert_eq!(f.file_name.as_deref(), Some("src/main.rs"));
+        assert_eq!(f.line_number, None);
+    }
+
+    #[test]
+    fn set_location_keeps_the_first_location_of_inlined_frames() {
+        let mut f = frame("app", "main", None, None);
+        set_location(&mut f, "./src/display.rs:40:5");
+        set_location(&mut f, "./src/display.rs:7:13");
+
+        assert_eq!(f.file_name.as_deref(), Some("./src/display.rs"));
+        assert_eq!(f.line_number, Some(40));
+    }
+}
@@ -0,0 +1,374 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
+
+use jni::objects::{JClass, JObject};
+use jni::JNIEnv;
+
+use crate::loader::{for_name, global_loader, global_loader_id, Loader};
+use crate::reference::Global;
+use crate::reflect::find_members;
+use crate::exception::{catch, report, suspend_exception, take_exception, with_exception_policy, ExceptionPolicy};
+use crate::{BindingErrors, Error, MemberKind};
+
+/// how the method and field IDs of a binding are cached.
+///
+/// set per binding with `cache lazy|eager|none;`, the default is `Lazy`,
+/// or `Eager` / `None` with the `cache-eager` / `cache-none` features.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStrategy {
+    /// each member is resolved on first use.
+    Lazy,
+    /// every member is resolved the first time the class is looked up.
+    Eager,
+    /// members are resolved on every call.
+    ///
+    /// the class itself is still cached per class loader,
+    /// redefining a class keeps the same class object.
+    None,
+}
+
+impl CacheStrategy {
+    /// the strategy of bindings that do not set one, selected by the `cache-*` features.
+    pub const DEFAULT: Self = if cfg!(feature = "cache-none") {
+        Self::None
+    } else if cfg!(feature = "cache-eager") {
+        Self::Eager
+    } else {
+        Self::Lazy
+    };
+}
+
+type Preload = fn(&mut JNIEnv) -> Result<(), BindingErrors>;
+
+/// identifies the vm and class loader that cached values were resolved in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CacheKey {
+    vm: usize,
+    loader: u64,
+}
+
+fn vm_key() -> usize {
+    crate::java_vm()
+        .map(|vm| vm.get_java_vm_pointer() as usize)
+        .unwrap_or(0)
+}
+
+/// a class resolved once per vm and class loader, held as a global reference.
+#[doc(hidden)]
+pub struct ClassCache {
+    /// the name of the binding type.
+    binding: &'static str,
+    /// the binary name of the class.
+    name: &'static str,
+    strategy: CacheStrategy,
+    /// whether looking up the class may run its static initializer.
+    initialize: bool,
+    /// resolves every member of the binding, for `CacheStrategy::Eager`.
+    preload: Preload,
+    /// the class loader set for this class only.
+    loader: RwLock<Option<Arc<Loader>>>,
+    loader_id: AtomicU64,
+    /// the class resolved in each vm and class loader.
+    ///
+    /// entries are only ever added, their global references are never deleted
+    /// since other threads may still be using them.
+    classes: RwLock<Vec<(CacheKey, &'static JClass<'static>)>>,
+}
+
+impl ClassCache {
+    pub const fn new(
+        binding: &'static str,
+        name: &'static str,
+        strategy: CacheStrategy,
+        initialize: bool,
+        preload: Preload,
+    ) -> Self {
+        Self {
+            binding,
+            name,
+            strategy,
+            initialize,
+            preload,
+            loader: RwLock::new(None),
+            loader_id: AtomicU64::new(0),
+            classes: RwLock::new(Vec::new()),
+        }
+    }
+
+    fn key(&self) -> CacheKey {
+        let loader = match self.loader_id.load(Ordering::Acquire) {
+            0 => global_loader_id(),
+            id => id,
+        };
+
+        CacheKey {
+            vm: vm_key(),
+            loader,
+        }
+    }
+
+    fn loader(&self) -> Option<Arc<Loader>> {
+        let loader = self.loader.read().unwrap_or_else(PoisonError::into_inner).clone();
+
+        loader.or_else(global_loader)
+    }
+
+    /// sets the class loader used to look up this class, overriding the global one.
+    pub fn set_loader(&self, env: &JNIEnv, loader: &JObject) -> Result<(), Error> {
+        if loader.is_null() {
+            self.clear_loader();
+            return Ok(());
+        }
+
+        let loader = Loader::new(env, loader)?;
+        let id = loader.id;
+
+        *self.loader.write().unwrap_or_else(PoisonError::into_inner) = Some(loader);
+        self.loader_id.store(id, Ordering::Release);
+
+        Ok(())
+    }
+
+    /// goes back to the global class loader.
+    pub fn clear_loader(&self) {
+        self.loader_id.store(0, Ordering::Release);
+        *self.loader.write().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+
+    /// the class cached for `key`.
+    fn cached(&self, key: CacheKey) -> Option<&'static JClass<'static>> {
+        let classes = self.classes.read().unwrap_or_else(PoisonError::into_inner);
+
+        classes.iter().find(|(k, _)| *k == key).map(|(_, class)| *class)
+    }
+
+    /// returns the class, looking it up on first use.
+    ///
+    /// the returned reference is global and lives as long as the vm.
+    pub fn get(&self, env: &mut JNIEnv) -> Result<&'static JClass<'static>, Error> {
+        self.resolve(env).map(|(_, class)| class)
+    }
+
+    /// returns the class and the key it is cached under,
+    /// which may differ from `key()` if the class loader changed meanwhile.
+    fn resolve(&self, env: &mut JNIEnv) -> Result<(CacheKey, &'static JClass<'static>), Error> {
+        crate::vm::ensure_running()?;
+        crate::vm::register_env(env);
+
+        let key = self.key();
+        if let Some(class) = self.cached(key) {
+            return Ok((key, class));
+        }
+
+        let loader = self.loader();
+
+        let local = match (&loader, self.initialize) {
+            (Some(loader), true) => loader.load_class(env, self.name),
+            (None, true) => env.find_class(self.name),
+            (Some(loader), false) => for_name(env, self.name, false, loader.as_obj()),
+            (None, false) => for_name(env, self.name, false, &JObject::null()),
+        };
+        let local = local.map_err(|e| self.class_error(env, e))?;
+        let global = Global::new(env, &local);
+        let _ = env.delete_local_ref(local);
+        let global = global.map_err(|e| match e {
+            // `NewGlobalRef` leaves an `OutOfMemoryError` pending when it fails
+            Error::Jni(e) => self.report(catch(env, e), "<clinit>"),
+            e => e,
+        })?;
+
+        let key = CacheKey {
+            vm: vm_key(),
+            loader: loader.map(|l| l.id).unwrap_or(0),
+        };
+
+        let class = {
+            let mut classes = self.classes.write().unwrap_or_else(PoisonError::into_inner);
+
+            // another thread resolved the class first, ours was never handed out
+            if let Some((_, class)) = classes.iter().find(|(k, _)| *k == key) {
+                return Ok((key, class));
+            }
+
+            let class: &'static JClass<'static> = Box::leak(Box::new(JClass::from(global.leak())));
+            classes.push((key, class));
+            class
+        };
+
+        // resolving members initializes the class, `initialize` preloads them otherwise
+        if self.strategy == CacheStrategy::Eager && self.initialize {
+            self.warm_up(env);
+        }
+
+        Ok((key, class))
+    }
+
+    /// runs the static initializer of the class if it has not run yet.
+    pub fn initialize(&self, env: &mut JNIEnv) -> Result<(), Error> {
+        let class = self.get(env)?;
+
+        let loader = env
+            .call_method(class, "getClassLoader", "()Ljava/lang/ClassLoader;", &[])
+            .and_then(|v| v.l())
+            .map_err(|e| self.report(catch(env, e), "<clinit>"))?;
+        let initialized = for_name(env, self.name, true, &loader);
+        let _ = env.delete_local_ref(loader);
+        let initialized = initialized.map_err(|e| self.report(catch(env, e), "<clinit>"))?;
+        let _ = env.delete_local_ref(initialized);
+
+        if self.strategy == CacheStrategy::Eager && !self.initialize {
+            self.warm_up(env);
+        }
+
+        Ok(())
+    }
+
+    /// resolves every member for `CacheStrategy::Eager`.
+    ///
+    /// members that fail to resolve are reported again when used, so their errors are dropped
+    /// and their exceptions cleared whatever the `ExceptionPolicy`.
+    fn warm_up(&self, env: &mut JNIEnv) {
+        suspend_exception(env, |env| {
+            let _ = with_exception_policy(ExceptionPolicy::Clear, || (self.preload)(env));
+        });
+    }
+
+    /// converts a failed class lookup, capturing the pending exception.
+    fn class_error(&self, env: &mut JNIEnv, error: jni::errors::Error) -> Error {
+        let Some(exception) = take_exception(env) else {
+            return Error::Jni(error);
+        };
+        report(self.binding, self.name, "<clinit>", &exception);
+
+        if exception.is_instance_of(env, "java/lang/ClassNotFoundException")
+            || exception.is_instance_of(env, "java/lang/NoClassDefFoundError")
+        {
+            return Error::ClassNotFound { class: self.name };
+        }
+
+        Error::JavaException(exception)
+    }
+
+    /// returns the member ID cached in `cache`, resolving it against this class with `resolve`
+    /// as selected by the `CacheStrategy`.
+    ///
+    /// `kind`, `name` and `descriptor` describe the member if it cannot be found.
+    pub fn member<'local, T: Copy>(
+        &self,
+        env: &mut JNIEnv<'local>,
+        cache: &IdCache<T>,
+        kind: MemberKind,
+        name: &'static str,
+        descriptor: &'static str,
+        resolve: impl FnOnce(&mut JNIEnv<'local>, &JClass<'static>, &str, &str) -> Result<T, jni::errors::Error>,
+    ) -> Result<T, Error> {
+        crate::vm::ensure_running()?;
+        crate::vm::register_env(env);
+
+        let key = self.key();
+
+        if self.strategy != CacheStrategy::None {
+            if let Some(id) = cache.get(key) {
+                return Ok(id);
+            }
+        }
+
+        // cached under the key of the class it was resolved against
+        let (key, class) = self.resolve(env)?;
+        let id = resolve(env, class, name, descriptor)
+            .map_err(|e| self.member_error(env, e, class, kind, name, descriptor))?;
+
+        if self.strategy != CacheStrategy::None {
+            cache.set(key, id);
+        }
+
+        Ok(id)
+    }
+
+    /// converts a failed member lookup, capturing the pending exception
+    /// and listing the members of `class` with the same name.
+    fn member_error(
+        &self,
+        env: &mut JNIEnv,
+        error: jni::errors::Error,
+        class: &JClass,
+        kind: MemberKind,
+        name: &'static str,
+        descriptor: &'static str,
+    ) -> Error {
+        let Some(exception) = take_exception(env) else {
+            return Error::Jni(error);
+        };
+        report(self.binding, self.name, name, &exception);
+
+        if exception.is_instance_of(env, "java/lang/NoSuchMethodError")
+            || exception.is_instance_of(env, "java/lang/NoSuchFieldError")
+        {
+            return Error::MemberNotFound {
+                class: self.name,
+                kind,
+                name,
+                descriptor,
+                found: suspend_exception(env, |env| find_members(env, class, kind, name)),
+            };
+        }
+
+        Error::JavaException(exception)
+    }
+
+    /// calls the exception hook if `error` is a java exception thrown by `member`, and returns it.
+    pub fn report(&self, error: Error, member: &'static str) -> Error {
+        if let Error::JavaException(exception) = &error {
+            report(self.binding, self.name, member, exception);
+        }
+
+        error
+    }
+
+    /// captures the java exception left pending behind `Error::Jni` by a conversion or type check
+    /// made for `member`, and reports it.
+    ///
+    /// other errors have already been reported where they were caught and are returned unchanged.
+    pub fn catch(&self, env: &mut JNIEnv, error: Error, member: &'static str) -> Error {
+        match error {
+            Error::Jni(e) => self.report(catch(env, e), member),
+            e => e,
+        }
+    }
+
+    /// the binary name of the class.
+    pub(crate) fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+/// a method or field ID resolved once per vm and class loader.
+#[doc(hidden)]
+pub struct IdCache<T> {
+    slot: RwLock<Option<(CacheKey, T)>>,
+}
+
+impl<T: Copy> IdCache<T> {
+    pub const fn new() -> Self {
+        Self {
+            slot: RwLock::new(None),
+        }
+    }
+
+    fn get(&self, key: CacheKey) -> Option<T> {
+        match *self.slot.read().unwrap_or_else(PoisonError::into_inner) {
+            Some((k, id)) if k == key => Some(id),
+            _ => None,
+        }
+    }
+
+    fn set(&self, key: CacheKey, id: T) {
+        *self.slot.write().unwrap_or_else(PoisonError::into_inner) = Some((key, id));
+    }
+}
+
+impl<T: Copy> Default for IdCache<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,108 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use jni::objects::{JClass, JObject};
+use jni::sys::{jobject, jvalue};
+use jni::JNIEnv;
+
+use crate::cache::ClassCache;
+use crate::object::class_name;
+use crate::{Error, JReturnType};
+
+static CHECKED: AtomicBool = AtomicBool::new(cfg!(any(feature = "checked", debug_assertions)));
+
+/// turns checked mode on or off for every thread from now on,
+/// e.g. off for debug builds too slow to run with the checks.
+///
+/// checked mode is on by default with the `checked` feature and in debug builds.
+pub fn set_checked_mode(checked: bool) {
+    CHECKED.store(checked, Ordering::Relaxed);
+}
+
+/// whether generated calls check that argument and returned objects are instances of their declared types.
+pub fn checked_mode() -> bool {
+    CHECKED.load(Ordering::Relaxed)
+}
+
+/// the class name of `obj` if it is not null and not an instance of `class`.
+///
+/// a failed check is returned as `Error::Jni`, leaving the exception pending for `ClassCache::catch`.
+///
+/// # Safety
+///
+/// `obj` must be a valid reference or null.
+#[doc(hidden)]
+pub unsafe fn type_mismatch(env: &mut JNIEnv, obj: jobject, class: &JClass) -> Result<Option<String>, Error> {
+    let obj = JObject::from_raw(obj);
+
+    if obj.is_null() || env.is_instance_of(&obj, class)? {
+        return Ok(None);
+    }
+
+    Ok(Some(class_name(env, &obj).unwrap_or_default().replace('.', "/")))
+}
+
+/// in checked mode, checks that the argument `name` of `member` is an instance of `T`,
+/// reporting a java exception thrown by the check against `member` of `class`.
+///
+/// # Safety
+///
+/// `value` must hold a `T`.
+#[doc(hidden)]
+pub unsafe fn check_argument<T: JReturnType>(
+    env: &mut JNIEnv,
+    value: &jvalue,
+    class: &'static ClassCache,
+    member: &'static str,
+    name: &'static str,
+) -> Result<(), Error> {
+    if !checked_mode() {
+        return Ok(());
+    }
+
+    match T::type_mismatch(env, value).map_err(|e| class.catch(env, e, member))? {
+        None => Ok(()),
+        Some(found) => Err(Error::WrongType {
+            class: class.name(),
+            member,
+            value: name,
+            expected: T::NAME,
+            found,
+        }),
+    }
+}
+
+/// in checked mode, checks that the value returned by `member` is an instance of `T`,
+/// deleting its local reference if it is not.
+///
+/// a java exception thrown by the check is reported against `member` of `class`.
+///
+/// # Safety
+///
+/// `value` must hold a `T` returned from java.
+#[doc(hidden)]
+pub unsafe fn check_return<T: JReturnType>(
+    env: &mut JNIEnv,
+    value: &jvalue,
+    class: &'static ClassCache,
+    member: &'static str,
+) -> Result<(), Error> {
+    if !checked_mode() {
+        return Ok(());
+    }
+
+    match T::type_mismatch(env, value).map_err(|e| class.catch(env, e, member))? {
+        None => Ok(()),
+        Some(found) => {
+            // only objects can mismatch
+            let _ = env.delete_local_ref(JObject::from_raw(value.l));
+
+            Err(Error::WrongType {
+                class: class.name(),
+                member,
+                value: "return value",
+                expected: T::NAME,
+                found,
+            })
+        }
+    }
+}
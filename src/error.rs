@@ -0,0 +1,103 @@
+use core::fmt;
+
+use crate::reflect::fmt_found;
+use crate::{FoundMember, JavaException, MemberKind};
+
+/// the error returned by bindings.
+#[derive(Debug)]
+pub enum Error {
+    /// an error from the `jni` crate.
+    Jni(jni::errors::Error),
+    /// `jni_bind::shutdown` was called, the vm can no longer be used.
+    VmDestroyed,
+    /// a java exception was thrown, it has been captured and cleared.
+    JavaException(JavaException),
+    /// the class of a binding could not be found.
+    ClassNotFound {
+        /// the binary name of the class.
+        class: &'static str,
+    },
+    /// a constructor, field or method of a binding could not be found.
+    MemberNotFound {
+        /// the binary name of the class.
+        class: &'static str,
+        kind: MemberKind,
+        name: &'static str,
+        /// the descriptor the binding expects.
+        descriptor: &'static str,
+        /// the members with the same name found by reflection, e.g. overloads with other descriptors.
+        found: Vec<FoundMember>,
+    },
+    /// a constructor or allocation returned null without throwing.
+    NullResult {
+        /// the binary name of the class.
+        class: &'static str,
+        member: &'static str,
+    },
+    /// in checked mode, an argument, field value or return value is not an instance of its declared type.
+    WrongType {
+        /// the binary name of the class.
+        class: &'static str,
+        member: &'static str,
+        /// the name of the argument, `value` for a field or `return value`.
+        value: &'static str,
+        /// the binary name of the declared type.
+        expected: &'static str,
+        /// the binary name of the class of the object.
+        found: String,
+    },
+    /// an object or class to throw is not a subclass of `java.lang.Throwable`.
+    NotThrowable {
+        /// the binary name of the class.
+        class: &'static str,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Jni(e) => fmt::Display::fmt(e, f),
+            Error::VmDestroyed => f.write_str("the java vm has been destroyed"),
+            Error::JavaException(e) => write!(f, "java exception thrown: {}", e),
+            Error::ClassNotFound { class } => write!(f, "class {} not found", class),
+            Error::MemberNotFound {
+                class,
+                kind,
+                name,
+                descriptor,
+                found,
+            } => {
+                write!(f, "{} {} {} not found in {}", kind, name, descriptor, class)?;
+                fmt_found(found, f)
+            }
+            Error::NullResult { class, member } => {
+                write!(f, "{} of {} returned null", member, class)
+            }
+            Error::WrongType {
+                class,
+                member,
+                value,
+                expected,
+                found,
+            } => write!(f, "{} of {} in {} is a {}, expected {}", value, member, class, found, expected),
+            Error::NotThrowable { class } => write!(f, "{} is not throwable", class),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Jni(e) => Some(e),
+            // the exception itself is part of the message, its causes follow
+            Error::JavaException(e) => std::error::Error::source(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<jni::errors::Error> for Error {
+    fn from(e: jni::errors::Error) -> Self {
+        Error::Jni(e)
+    }
+}
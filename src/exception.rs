@@ -0,0 +1,291 @@
+use core::fmt;
+use std::backtrace::Backtrace;
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
+
+use jni::objects::{JClass, JObject};
+use jni::JNIEnv;
+
+use crate::backtrace::{combine, CombinedBacktrace};
+use crate::object::{call_string_method, class_name};
+use crate::throwable::stack_trace;
+use crate::{Error, JObjectType, ObjectRef, StackTraceElement, Throwable};
+
+/// what bindings do with a java exception thrown by a call, once it has been captured into an `Error`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExceptionPolicy {
+    /// clear the exception.
+    #[default]
+    Clear,
+    /// print the exception and its stack trace with `ExceptionDescribe`, then clear it.
+    Describe,
+    /// leave the exception pending, e.g. for a native method to return with it thrown in java.
+    ///
+    /// no other JNI call may be made until it is cleared.
+    Propagate,
+}
+
+static POLICY: AtomicU8 = AtomicU8::new(ExceptionPolicy::Clear as u8);
+
+thread_local! {
+    static POLICY_OVERRIDE: Cell<Option<ExceptionPolicy>> = const { Cell::new(None) };
+}
+
+/// sets the exception policy used by every thread from now on, `ExceptionPolicy::Clear` by default.
+pub fn set_exception_policy(policy: ExceptionPolicy) {
+    POLICY.store(policy as u8, Ordering::Relaxed);
+}
+
+/// the exception policy in effect on the current thread.
+pub fn exception_policy() -> ExceptionPolicy {
+    if let Some(policy) = POLICY_OVERRIDE.with(Cell::get) {
+        return policy;
+    }
+
+    match POLICY.load(Ordering::Relaxed) {
+        1 => ExceptionPolicy::Describe,
+        2 => ExceptionPolicy::Propagate,
+        _ => ExceptionPolicy::Clear,
+    }
+}
+
+/// runs `f` with `policy` in effect for the calls it makes on the current thread.
+///
+/// ```ignore
+/// let r = jni_bind::with_exception_policy(ExceptionPolicy::Propagate, || parser.parse(env, &input));
+/// ```
+pub fn with_exception_policy<R>(policy: ExceptionPolicy, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<ExceptionPolicy>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            POLICY_OVERRIDE.with(|p| p.set(self.0));
+        }
+    }
+
+    let _restore = Restore(POLICY_OVERRIDE.with(|p| p.replace(Some(policy))));
+
+    f()
+}
+
+/// a java exception thrown by a call through a binding.
+///
+/// the exception is cleared when it is captured.
+#[derive(Debug, Clone)]
+pub struct JavaException {
+    /// the thrown object.
+    pub throwable: Throwable,
+    /// the binary name of the class of the exception, e.g. `java.io.IOException`.
+    pub class: String,
+    /// `getMessage()`
+    pub message: Option<String>,
+    /// `getStackTrace()`
+    pub stack_trace: Vec<StackTraceElement>,
+    /// the rust backtrace of the call that captured the exception,
+    /// captured as configured by `RUST_BACKTRACE` and `RUST_LIB_BACKTRACE`.
+    pub backtrace: Arc<Backtrace>,
+}
+
+impl JavaException {
+    /// captures `throwable`, which must not be null.
+    pub(crate) fn new(env: &mut JNIEnv, throwable: &JObject) -> Result<Self, Error> {
+        let class = class_name(env, throwable).unwrap_or_default();
+        let message = call_string_method(env, throwable, "getMessage");
+        let stack_trace = stack_trace(env, throwable).unwrap_or_default();
+
+        Ok(Self {
+            throwable: unsafe { Throwable::from_object_ref(ObjectRef::new::<Throwable>(env, throwable)?) },
+            class,
+            message,
+            stack_trace,
+            backtrace: Arc::new(Backtrace::capture()),
+        })
+    }
+
+    /// the java stack trace interleaved with the rust backtrace, for reports spanning both languages.
+    pub fn combined_backtrace(&self) -> CombinedBacktrace<'_> {
+        combine(&self.stack_trace, &self.backtrace)
+    }
+
+    /// whether the exception is an instance of the binary name `class`, e.g. `java/io/IOException`.
+    pub fn is_instance_of(&self, env: &mut JNIEnv, class: &str) -> bool {
+        suspend_exception(env, |env| {
+            let r = env.is_instance_of(self.throwable.object_ref().as_obj(), class);
+            clear_pending(env);
+
+            r.unwrap_or(false)
+        })
+    }
+}
+
+impl fmt::Display for JavaException {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.class)?;
+
+        if let Some(message) = &self.message {
+            write!(f, ": {}", message)?;
+        }
+
+        for element in &self.stack_trace {
+            write!(f, "\n\tat {}", element)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for JavaException {
+    /// the cause of the exception, see `Throwable::source`.
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        std::error::Error::source(&self.throwable)
+    }
+}
+
+/// a java exception caught by a binding, as passed to the exception hook.
+#[derive(Debug, Clone, Copy)]
+pub struct ExceptionInfo<'a> {
+    /// the name of the binding type, e.g. `Integer`.
+    pub binding: &'static str,
+    /// the binary name of the bound class, e.g. `java/lang/Integer`.
+    pub class: &'static str,
+    /// the java name of the member that threw, `<init>` for constructors
+    /// and `<clinit>` for class lookups and static initializers.
+    ///
+    /// calls made outside java members are reported under the rust helper or jni function
+    /// that made them, e.g. `downcast`, `throw` or `AllocObject`.
+    pub member: &'static str,
+    pub exception: &'a JavaException,
+}
+
+static HOOK: RwLock<Option<fn(&ExceptionInfo)>> = RwLock::new(None);
+
+/// sets a hook called by every binding on every thread whenever it catches a java exception,
+/// e.g. to log or count exceptions per api.
+///
+/// ```ignore
+/// static TIMEOUTS: AtomicUsize = AtomicUsize::new(0);
+///
+/// jni_bind::set_exception_hook(|info| {
+///     if info.member == "get" && info.exception.class == "java.util.concurrent.TimeoutException" {
+///         TIMEOUTS.fetch_add(1, Ordering::Relaxed);
+///     }
+/// });
+/// ```
+///
+/// the hook runs before the error is returned, with the exception already cleared
+/// or pending as set by the `ExceptionPolicy`.
+pub fn set_exception_hook(hook: fn(&ExceptionInfo)) {
+    *HOOK.write().unwrap_or_else(PoisonError::into_inner) = Some(hook);
+}
+
+/// removes the exception hook.
+pub fn clear_exception_hook() {
+    *HOOK.write().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// calls the exception hook, if any, with `exception` caught by `member` of the binding `binding`.
+pub(crate) fn report(binding: &'static str, class: &'static str, member: &'static str, exception: &JavaException) {
+    // copied out so the hook may set another one
+    let Some(hook) = *HOOK.read().unwrap_or_else(PoisonError::into_inner) else {
+        return;
+    };
+
+    hook(&ExceptionInfo {
+        binding,
+        class,
+        member,
+        exception,
+    });
+}
+
+fn clear_pending(env: &mut JNIEnv) {
+    if env.exception_check().unwrap_or(false) {
+        let _ = env.exception_clear();
+    }
+}
+
+/// captures the pending exception, if any, then clears, describes or leaves it pending
+/// according to the `ExceptionPolicy`.
+pub(crate) fn take_exception(env: &mut JNIEnv) -> Option<JavaException> {
+    if !env.exception_check().unwrap_or(false) {
+        return None;
+    }
+
+    let throwable = env.exception_occurred().ok()?;
+    let policy = exception_policy();
+
+    if policy == ExceptionPolicy::Describe {
+        let _ = env.exception_describe();
+    }
+    let _ = env.exception_clear();
+
+    let exception = JavaException::new(env, &throwable).ok();
+
+    if policy == ExceptionPolicy::Propagate {
+        let _ = env.throw(&throwable);
+    }
+    let _ = env.delete_local_ref(throwable);
+
+    exception
+}
+
+/// runs `f` with the pending exception, if any, cleared, and throws it again afterwards.
+///
+/// lets bindings make JNI calls after capturing an exception left pending by `ExceptionPolicy::Propagate`.
+#[doc(hidden)]
+pub fn suspend_exception<'local, R>(env: &mut JNIEnv<'local>, f: impl FnOnce(&mut JNIEnv<'local>) -> R) -> R {
+    let pending = match env.exception_check() {
+        Ok(true) => env.exception_occurred().ok(),
+        _ => None,
+    };
+    if pending.is_some() {
+        let _ = env.exception_clear();
+    }
+
+    let r = f(env);
+
+    if let Some(throwable) = pending {
+        clear_pending(env);
+        let _ = env.throw(&throwable);
+        let _ = env.delete_local_ref(throwable);
+    }
+
+    r
+}
+
+/// converts an error from the `jni` crate, capturing and clearing the pending java exception.
+#[doc(hidden)]
+pub fn catch(env: &mut JNIEnv, error: jni::errors::Error) -> Error {
+    match take_exception(env) {
+        Some(exception) => Error::JavaException(exception),
+        None => Error::Jni(error),
+    }
+}
+
+/// like `catch`, but a null result without an exception becomes `Error::NullResult`.
+#[doc(hidden)]
+pub fn catch_null(
+    env: &mut JNIEnv,
+    error: jni::errors::Error,
+    class: &'static str,
+    member: &'static str,
+) -> Error {
+    match catch(env, error) {
+        Error::Jni(jni::errors::Error::NullPtr(_)) => Error::NullResult { class, member },
+        e => e,
+    }
+}
+
+/// whether `obj` is an instance of `class`, and `class` is a subclass of `than` if given.
+#[doc(hidden)]
+pub fn is_more_specific(env: &mut JNIEnv, obj: &JObject, class: &JClass, than: Option<&JClass>) -> bool {
+    let r = env.is_instance_of(obj, class).and_then(|is| match (is, than) {
+        (false, _) => Ok(false),
+        (true, None) => Ok(true),
+        (true, Some(than)) => env.is_assignable_from(class, than),
+    });
+    clear_pending(env);
+
+    r.unwrap_or(false)
+}
@@ -0,0 +1,119 @@
+use jni::signature::{Primitive, ReturnType};
+use jni::sys::{jclass, jmethodID, jobject, jvalue};
+use jni::JNIEnv;
+
+/// calls `Call<Type>MethodA` for `ret` directly, without checking the arguments.
+///
+/// a pending exception is left for the caller to check.
+#[doc(hidden)]
+pub unsafe fn call_method_a(
+    env: &JNIEnv,
+    ret: ReturnType,
+    obj: jobject,
+    method: jmethodID,
+    args: *const jvalue,
+) -> jvalue {
+    let env = env.get_raw();
+    // the function table of a valid env is always fully populated
+    let table = &**env;
+
+    match ret {
+        ReturnType::Object | ReturnType::Array => jvalue {
+            l: table.CallObjectMethodA.unwrap_unchecked()(env, obj, method, args),
+        },
+        ReturnType::Primitive(p) => match p {
+            Primitive::Boolean => jvalue {
+                z: table.CallBooleanMethodA.unwrap_unchecked()(env, obj, method, args),
+            },
+            Primitive::Byte => jvalue {
+                b: table.CallByteMethodA.unwrap_unchecked()(env, obj, method, args),
+            },
+            Primitive::Char => jvalue {
+                c: table.CallCharMethodA.unwrap_unchecked()(env, obj, method, args),
+            },
+            Primitive::Short => jvalue {
+                s: table.CallShortMethodA.unwrap_unchecked()(env, obj, method, args),
+            },
+            Primitive::Int => jvalue {
+                i: table.CallIntMethodA.unwrap_unchecked()(env, obj, method, args),
+            },
+            Primitive::Long => jvalue {
+                j: table.CallLongMethodA.unwrap_unchecked()(env, obj, method, args),
+            },
+            Primitive::Float => jvalue {
+                f: table.CallFloatMethodA.unwrap_unchecked()(env, obj, method, args),
+            },
+            Primitive::Double => jvalue {
+                d: table.CallDoubleMethodA.unwrap_unchecked()(env, obj, method, args),
+            },
+            Primitive::Void => {
+                table.CallVoidMethodA.unwrap_unchecked()(env, obj, method, args);
+                jvalue { j: 0 }
+            }
+        },
+    }
+}
+
+/// calls `CallStatic<Type>MethodA` for `ret` directly, without checking the arguments.
+///
+/// a pending exception is left for the caller to check.
+#[doc(hidden)]
+pub unsafe fn call_static_method_a(
+    env: &JNIEnv,
+    ret: ReturnType,
+    class: jclass,
+    method: jmethodID,
+    args: *const jvalue,
+) -> jvalue {
+    let env = env.get_raw();
+    let table = &**env;
+
+    match ret {
+        ReturnType::Object | ReturnType::Array => jvalue {
+            l: table.CallStaticObjectMethodA.unwrap_unchecked()(env, class, method, args),
+        },
+        ReturnType::Primitive(p) => match p {
+            Primitive::Boolean => jvalue {
+                z: table.CallStaticBooleanMethodA.unwrap_unchecked()(env, class, method, args),
+            },
+            Primitive::Byte => jvalue {
+                b: table.CallStaticByteMethodA.unwrap_unchecked()(env, class, method, args),
+            },
+            Primitive::Char => jvalue {
+                c: table.CallStaticCharMethodA.unwrap_unchecked()(env, class, method, args),
+            },
+            Primitive::Short => jvalue {
+                s: table.CallStaticShortMethodA.unwrap_unchecked()(env, class, method, args),
+            },
+            Primitive::Int => jvalue {
+                i: table.CallStaticIntMethodA.unwrap_unchecked()(env, class, method, args),
+            },
+            Primitive::Long => jvalue {
+                j: table.CallStaticLongMethodA.unwrap_unchecked()(env, class, method, args),
+            },
+            Primitive::Float => jvalue {
+                f: table.CallStaticFloatMethodA.unwrap_unchecked()(env, class, method, args),
+            },
+            Primitive::Double => jvalue {
+                d: table.CallStaticDoubleMethodA.unwrap_unchecked()(env, class, method, args),
+            },
+            Primitive::Void => {
+                table.CallStaticVoidMethodA.unwrap_unchecked()(env, class, method, args);
+                jvalue { j: 0 }
+            }
+        },
+    }
+}
+
+/// a single `ExceptionCheck`, capturing and clearing the exception if one is pending.
+#[doc(hidden)]
+pub fn check_exception(env: &mut JNIEnv) -> Result<(), crate::Error> {
+    let raw = env.get_raw();
+    let pending = unsafe { (**raw).ExceptionCheck.unwrap_unchecked()(raw) };
+
+    if pending == jni::sys::JNI_TRUE {
+        return Err(crate::exception::catch(env, jni::errors::Error::JavaException));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use jni::objects::JObject;
+use jni::sys::jvalue;
+use jni::JNIEnv;
+
+use crate::{Error, JObjectType, JReturnType};
+
+/// runs `f` in a new local reference frame with room for at least `capacity` local references.
+///
+/// all local references created inside `f` are freed when the frame is popped.
+/// bound objects hold global references, so they can be returned from `f` and stay valid.
+pub fn with_local_frame<T, E, F>(env: &mut JNIEnv, capacity: i32, f: F) -> Result<T, E>
+where
+    F: FnOnce(&mut JNIEnv) -> Result<T, E>,
+    E: From<jni::errors::Error>,
+{
+    env.with_local_frame(capacity, f)
+}
+
+/// runs `f` in a new local reference frame and promotes the local reference it returns
+/// out of the frame into the bound type `T`.
+///
+/// fails with `Error::WrongType` if the returned reference is not an instance of `T`.
+#[track_caller]
+pub fn with_local_frame_returning<T, E, F>(env: &mut JNIEnv, capacity: i32, f: F) -> Result<T, E>
+where
+    T: JObjectType + JReturnType,
+    F: for<'local> FnOnce(&mut JNIEnv<'local>) -> Result<JObject<'local>, E>,
+    E: From<jni::errors::Error> + From<Error>,
+{
+    let obj = env.with_local_frame_returning_local(capacity, f)?;
+    let value = jvalue { l: obj.into_raw() };
+
+    // checked even without checked mode, nothing else ensures the reference is a `T`
+    let found = unsafe { T::type_mismatch(env, &value) }
+        .map_err(|e| T::class_cache().catch(env, e, "with_local_frame_returning"))?;
+
+    if let Some(found) = found {
+        let _ = env.delete_local_ref(unsafe { JObject::from_raw(value.l) });
+
+        return Err(Error::WrongType {
+            class: <T as JReturnType>::NAME,
+            member: "with_local_frame_returning",
+            value: "return value",
+            expected: <T as JReturnType>::NAME,
+            found,
+        }
+        .into());
+    }
+
+    unsafe { T::from_jvalue(env, value) }
+        .map_err(|e| T::class_cache().catch(env, e, "with_local_frame_returning").into())
+}
+
+/// ensures that at least `capacity` more local references can be created in the current frame.
+pub fn ensure_local_capacity(env: &mut JNIEnv, capacity: i32) -> Result<(), jni::errors::Error> {
+    env.ensure_local_capacity(capacity)
+}
@@ -9,10 +9,59 @@ pub use jni::JNIEnv;
 
 pub mod export {
     pub use const_format;
+    pub use inventory;
     pub use paste;
+
+    pub use crate::object::{
+        fmt_debug, fmt_display, identity_eq, identity_hash_code, java_equals, java_hash_code,
+    };
+    pub use crate::cache::{ClassCache, IdCache};
+    pub use crate::checked::{check_argument, check_return, type_mismatch};
+    pub use crate::vm::register_env;
+    pub use crate::exception::{catch, catch_null, is_more_specific, suspend_exception};
+    pub use crate::fast::check_exception;
+    pub use crate::reference::promote_local;
 }
 
+mod backtrace;
+mod cache;
+mod checked;
+mod error;
+mod exception;
+mod fast;
+mod frame;
+mod loader;
+mod object;
+mod preload;
 mod primitives;
+mod reference;
+mod reflect;
+mod registry;
+mod throw;
+mod throwable;
+#[cfg(feature = "ref-tracking")]
+pub mod ref_tracking;
+mod vm;
+
+pub use backtrace::CombinedBacktrace;
+pub use cache::CacheStrategy;
+pub use checked::{checked_mode, set_checked_mode};
+pub use error::Error;
+pub use exception::{
+    clear_exception_hook, exception_policy, set_exception_hook, set_exception_policy, with_exception_policy,
+    ExceptionInfo, ExceptionPolicy, JavaException,
+};
+pub use throw::{IntoJavaException, JThrowableType};
+pub use throwable::{RuntimeException, StackTraceElement, Throwable};
+pub use registry::{preload_all, registered_classes, RegisteredClass};
+pub use reference::{
+    drop_policy, pending_ref_count, release_pending_refs, set_drop_policy, DropPolicy, ObjectRef,
+};
+pub use preload::{BindingError, BindingErrors, MemberKind};
+pub use reflect::FoundMember;
+pub use frame::{ensure_local_capacity, with_local_frame, with_local_frame_returning};
+pub use loader::{clear_class_loader, set_class_loader, set_class_loader_from};
+pub use vm::{destroy_java_vm, is_shut_down, java_vm, set_java_vm, shutdown};
 
 /// this trait should only be implemented by macro.
 /// Manually implementing this trait may cause undefined behaviour
@@ -25,7 +74,37 @@ pub unsafe trait JReturnType {
     const NAME: &'static str;
     const JNI_RETURN_TY: jni::signature::ReturnType;
 
-    unsafe fn from_jvalue(env: &mut JNIEnv, value: jvalue) -> Self;
+    /// converts a value returned from java.
+    ///
+    /// object types take ownership of the local reference in `value`,
+    /// which is deleted once it has been promoted to a global reference.
+    /// a failure to create the global reference is returned as `Error::Jni`,
+    /// leaving the exception pending for the caller to capture and report with `ClassCache::catch`.
+    #[track_caller]
+    unsafe fn from_jvalue(env: &mut JNIEnv, value: jvalue) -> Result<Self, Error>
+    where
+        Self: Sized;
+
+    /// the class name of the object in `value` if it is not an instance of `Self`, used in checked mode.
+    ///
+    /// primitives always match. a failed check is returned as `Error::Jni`,
+    /// leaving the exception pending like `from_jvalue`.
+    #[doc(hidden)]
+    unsafe fn type_mismatch(_env: &mut JNIEnv, _value: &jvalue) -> Result<Option<String>, Error> {
+        Ok(None)
+    }
+
+    /// calls an instance method returning `Self` through `Call<Type>MethodA`.
+    #[doc(hidden)]
+    unsafe fn call_method_a(env: &JNIEnv, obj: jobject, method: jmethodID, args: *const jvalue) -> jvalue {
+        fast::call_method_a(env, Self::JNI_RETURN_TY, obj, method, args)
+    }
+
+    /// calls a static method returning `Self` through `CallStatic<Type>MethodA`.
+    #[doc(hidden)]
+    unsafe fn call_static_method_a(env: &JNIEnv, class: jclass, method: jmethodID, args: *const jvalue) -> jvalue {
+        fast::call_static_method_a(env, Self::JNI_RETURN_TY, class, method, args)
+    }
 }
 
 unsafe impl JReturnType for () {
@@ -34,8 +113,91 @@ unsafe impl JReturnType for () {
     const JNI_RETURN_TY: jni::signature::ReturnType =
         jni::signature::ReturnType::Primitive(jni::signature::Primitive::Void);
 
-    unsafe fn from_jvalue(_env: &mut JNIEnv, _value: jvalue) -> Self {
-        ()
+    unsafe fn from_jvalue(_env: &mut JNIEnv, _value: jvalue) -> Result<Self, Error> {
+        Ok(())
+    }
+}
+
+/// a bound class or interface, implemented by `import_class!` and `import_interface!`.
+///
+/// # Safety
+///
+/// this trait should only be implemented by macro,
+/// `from_object_ref` assumes `Self` is a transparent wrapper around `ObjectRef`.
+pub unsafe trait JObjectType: JBindingType + Sized {
+    /// looks up the class of this binding.
+    ///
+    /// the returned reference is global, cached for as long as the vm runs.
+    fn class(env: &mut JNIEnv) -> Result<&'static jni::objects::JClass<'static>, Error>;
+
+    /// runs the static initializer of the class if it has not run yet.
+    fn initialize(env: &mut JNIEnv) -> Result<(), Error>;
+
+    /// resolves the class and every constructor, field and method of this binding,
+    /// reporting all the members that could not be resolved.
+    fn preload(env: &mut JNIEnv) -> Result<(), BindingErrors>;
+
+    /// sets the class loader used to look up this class, overriding the global one.
+    fn set_class_loader(env: &JNIEnv, loader: &jni::objects::JObject) -> Result<(), Error>;
+
+    /// goes back to looking up this class with the global class loader.
+    fn clear_class_loader();
+
+    /// allocates an instance with `AllocObject`, without running any constructor.
+    ///
+    /// fails with an `InstantiationException` for interfaces and abstract classes.
+    ///
+    /// # Safety
+    ///
+    /// every field is left zeroed or null, the object must be fully initialized
+    /// (e.g. with the field setters) before java code relying on its constructors sees it.
+    #[track_caller]
+    unsafe fn alloc_uninit(env: &mut JNIEnv) -> Result<Self, Error>;
+
+    /// the reference held by this object.
+    fn object_ref(&self) -> &ObjectRef;
+
+    /// wraps a reference without checking its class.
+    #[doc(hidden)]
+    unsafe fn from_object_ref(obj: ObjectRef) -> Self;
+
+    /// the cache of this class, which reports the exceptions caught for this binding.
+    #[doc(hidden)]
+    fn class_cache() -> &'static export::ClassCache;
+
+    /// creates an independent global reference to this object.
+    ///
+    /// `clone()` shares the reference of `self` instead.
+    #[track_caller]
+    fn new_ref(&self, env: &JNIEnv) -> Result<Self, Error> {
+        Ok(unsafe { Self::from_object_ref(self.object_ref().new_ref(env)?) })
+    }
+
+    /// whether `self` and `other` share the same global reference.
+    fn shares_ref(&self, other: &impl JObjectType) -> bool {
+        self.object_ref().shares_ref(other.object_ref())
+    }
+
+    /// converts to a superclass or interface, sharing the reference of `self`.
+    fn upcast<T: JObjectType>(&self) -> T
+    where
+        Self: IsA<T>,
+    {
+        unsafe { T::from_object_ref(self.object_ref().clone()) }
+    }
+
+    /// converts to `T` if the object is an instance of it, sharing the reference of `self`.
+    fn downcast<T: JObjectType>(&self, env: &mut JNIEnv) -> Result<Option<T>, Error> {
+        let class = T::class(env)?;
+
+        if !env
+            .is_instance_of(self.object_ref().as_obj(), class)
+            .map_err(|e| Self::class_cache().report(export::catch(env, e), "downcast"))?
+        {
+            return Ok(None);
+        }
+
+        Ok(Some(unsafe { T::from_object_ref(self.object_ref().clone()) }))
     }
 }
 
@@ -56,24 +218,33 @@ macro_rules! import_class {
         $name: ident;
         $(extends $parent_class: ty;)?
         $(implements $($parent_interface: ty),+;)?
+        $(equality $equality:ident;)?
+        $(calls $calls:ident;)?
+        $(cache $cache:ident;)?
+        $(initialize $initialize:ident;)?
         $(
-            constructor ($($ctor_arg:ident : $ctor_arg_ty:ty),*);
+            constructor $ctor_args:tt;
         )?
         $(
             field $field:ident : $field_ty:ty;
         )*
         $(
-            static fn $static_method:ident ($($static_arg:ident : $static_arg_ty:ty),*) -> $static_ret:ty;
-        )*
-        $(
-            $(#[doc=$doc:expr])*
-            fn $method:ident (&self $(, $arg:ident : $arg_ty:ty)*) -> $ret:ty;
+            $(#[$($attr:tt)*])*
+            // `$static_marker` never matches, it only lets `static` be expanded again
+            $(static $(#$static_marker:tt)?)? fn $method:ident ($($params:tt)*) -> $ret:ty;
         )*
     ) => {
         #[repr(transparent)]
-        #[derive(Debug, Clone)]
         pub struct $name{
-            _obj: $crate::jni::objects::GlobalRef,
+            _obj: $crate::ObjectRef,
+        }
+
+        impl ::core::clone::Clone for $name {
+            fn clone(&self) -> Self {
+                Self {
+                    _obj: ::core::clone::Clone::clone(&self._obj),
+                }
+            }
         }
 
         unsafe impl $crate::JBindingType for $name {
@@ -86,7 +257,7 @@ macro_rules! import_class {
                 }
             }
 
-            unsafe fn to_jvalue_ref<'obj_ref>(&'obj_ref self) -> $crate::jni::objects::JValue<'_, 'obj_ref>{
+            unsafe fn to_jvalue_ref<'obj_ref>(&'obj_ref self) -> $crate::jni::objects::JValue<'obj_ref, 'obj_ref>{
                 $crate::jni::objects::JValue::Object(
                     self._obj.as_obj()
                 )
@@ -98,12 +269,15 @@ macro_rules! import_class {
             const NAME: &'static str = <Self as $crate::JBindingType>::NAME;
             const JNI_RETURN_TY: jni::signature::ReturnType = jni::signature::ReturnType::Object;
 
-            unsafe fn from_jvalue(env: &mut$crate::JNIEnv, value: $crate::jni::sys::jvalue) -> Self {
-                let o = $crate::jni::objects::JObject::from_raw(value.l);
-                let r = env.new_global_ref(o).expect("failed to create global ref");
-                Self {
-                    _obj: r,
-                }
+            unsafe fn from_jvalue(env: &mut$crate::JNIEnv, value: $crate::jni::sys::jvalue) -> Result<Self, $crate::Error> {
+                Ok(Self {
+                    _obj: $crate::export::promote_local::<Self>(env, value.l)?,
+                })
+            }
+
+            unsafe fn type_mismatch(env: &mut $crate::JNIEnv, value: &$crate::jni::sys::jvalue) -> Result<Option<::std::string::String>, $crate::Error> {
+                let class = <Self as $crate::JObjectType>::class(env)?;
+                $crate::export::type_mismatch(env, value.l, class)
             }
         }
         
@@ -118,6 +292,22 @@ macro_rules! import_class {
                 self
             }
         }
+
+        $crate::__impl_fmt!($name);
+        $crate::__impl_object!($name);
+
+        $crate::export::inventory::submit! {
+            $crate::RegisteredClass::new(
+                concat!(module_path!(), "::", stringify!($name)),
+                $sig,
+                concat!("L", $sig, ";"),
+                <$name as $crate::JObjectType>::preload
+            )
+        }
+
+        $(
+            $crate::__impl_equality!($name, $equality);
+        )?
         
         
         $(
@@ -146,6 +336,12 @@ macro_rules! import_class {
                 }
             }
 
+            impl From<&$name> for $parent_class{
+                fn from(value: &$name) -> $parent_class{
+                    $crate::JObjectType::upcast(value)
+                }
+            }
+
             unsafe impl $crate::IsA<$parent_class> for $name{
                 unsafe fn as_ref(&self) -> &$parent_class{
                     ::core::convert::AsRef::as_ref(self)
@@ -176,6 +372,12 @@ macro_rules! import_class {
                         }
                     }
                 }
+
+                impl From<&$name> for $parent_interface{
+                    fn from(value: &$name) -> $parent_interface{
+                        $crate::JObjectType::upcast(value)
+                    }
+                }
     
                 unsafe impl $crate::IsA<$parent_interface> for $name{
                     unsafe fn as_ref(&self) -> &$parent_interface{
@@ -193,267 +395,659 @@ macro_rules! import_class {
 
         #[allow(unused)]
         impl $name {
-            #[allow(dead_code)]
-            fn class<'local>(env: &mut $crate::jni::JNIEnv<'local>) -> Result<$crate::jni::objects::JClass<'local>, $crate::jni::errors::Error>{
-                static CACHE: core::sync::atomic::AtomicU64 = core::sync::atomic::AtomicU64::new(0);
+            /// whether every method of this class uses the raw call path.
+            const __FAST_CALLS: bool = $crate::__fast_calls!($($calls)?);
 
-                let id = CACHE.load(core::sync::atomic::Ordering::Relaxed);
-
-                let mut env_ptr = id >> 32;
-                let mut class_ptr = id & 0xFFFFFFFF;
+            fn __class_cache() -> &'static $crate::export::ClassCache {
+                static CLASS: $crate::export::ClassCache = $crate::export::ClassCache::new(stringify!($name), $sig, $crate::__cache_strategy!($($cache)?), $crate::__initialize_on_lookup!($($initialize)?), <$name as $crate::JObjectType>::preload);
+                &CLASS
+            }
 
-                if env_ptr == 0 || env_ptr != env.get_raw() as u64 {
-                    env_ptr = env.get_raw() as u64;
+            #[allow(dead_code)]
+            fn class(env: &mut $crate::jni::JNIEnv) -> Result<&'static $crate::jni::objects::JClass<'static>, $crate::Error>{
+                Self::__class_cache().get(env)
+            }
 
-                    let class = env
-                        .find_class(<Self as $crate::JBindingType>::NAME)?;
+            /// see `JObjectType::preload`.
+            fn __preload(env: &mut $crate::jni::JNIEnv) -> Result<(), $crate::BindingErrors> {
+                let mut errors = $crate::BindingErrors::new(<Self as $crate::JBindingType>::NAME);
 
-                    class_ptr = class.as_raw() as u64;
+                let class = Self::class(env);
+                if !errors.check(env, $crate::MemberKind::Class, <Self as $crate::JBindingType>::NAME, <Self as $crate::JBindingType>::SIGNATURE, class) {
+                    return errors.into_result(env);
+                }
 
-                    let cache = (env_ptr << 32) | class_ptr;
+                $(
+                    let id = Self::__ctor_id(env);
+                    errors.check(env, $crate::MemberKind::Constructor, "<init>", $crate::__constructor_signature!($ctor_args), id);
+                )?
 
-                    CACHE.store(cache, core::sync::atomic::Ordering::Relaxed);
-                };
+                $crate::export::paste::paste!{
+                    $(
+                        let id = Self::[<__field_ $field:snake _id>](env);
+                        errors.check(env, $crate::MemberKind::Field, stringify!($field), <$field_ty as $crate::JReturnType>::SIGNATURE, id);
+                    )*
 
-                unsafe{
-                    return Ok($crate::jni::objects::JClass::from_raw(class_ptr as _))
                 }
+
+                $(
+                    $crate::__impl_member!{@preload errors env [$($($static_marker)? static)?] $method}
+                )*
+
+                errors.into_result(env)
             }
 
             $(
-                pub fn new(env: &mut $crate::jni::JNIEnv $(, $ctor_arg : impl $crate::IsA<$ctor_arg_ty>)*) -> Result<Self, $crate::jni::errors::Error> {
-                    let class = Self::class(env)?;
+                $crate::__impl_constructor!($ctor_args);
+            )?
 
-                    const CTOR_SIG: &str = $crate::export::const_format::concatcp!(
-                        "(",
-                        $(
-                            <$ctor_arg_ty as $crate::JBindingType>::SIGNATURE,
-                        )*
-                        ")V"
-                    );
+            $(
+                $crate::export::paste::paste!{
+                    fn [<__field_ $field:snake _id>](env: &mut $crate::jni::JNIEnv) -> Result<$crate::jni::objects::JFieldID, $crate::Error>{
+                        static FIELD_ID: $crate::export::IdCache<$crate::jni::objects::JFieldID> = $crate::export::IdCache::new();
+
+                        Self::__class_cache().member(
+                            env,
+                            &FIELD_ID,
+                            $crate::MemberKind::Field,
+                            stringify!($field),
+                            <$field_ty as $crate::JReturnType>::SIGNATURE,
+                            |env, class, name, sig| env.get_field_id(class, name, sig),
+                        )
+                    }
 
-                    static CACHE: core::sync::atomic::AtomicU64 = core::sync::atomic::AtomicU64::new(0);
+                    #[track_caller]
+                    pub fn [<get_ $field:snake>](&self, env: &mut $crate::jni::JNIEnv) -> Result<$field_ty, $crate::Error>{
+                        let field_id = Self::[<__field_ $field:snake _id>](env)?;
 
-                    let id = CACHE.load(core::sync::atomic::Ordering::Relaxed);
+                        unsafe{
+                            let b = env.get_field_unchecked(
+                                self._obj.as_obj(),
+                                field_id,
+                                <$field_ty as $crate::JReturnType>::JNI_RETURN_TY
+                            ).map_err(|e| Self::__class_cache().report($crate::export::catch(env, e), stringify!($field)))?.as_jni();
+                            $crate::export::check_return::<$field_ty>(env, &b, Self::__class_cache(), stringify!($field))?;
 
-                    let mut env_ptr = id >> 32;
-                    let mut method_id = id & 0xFFFFFFFF;
+                            return <$field_ty as $crate::JReturnType>::from_jvalue(env, b)
+                                .map_err(|e| Self::__class_cache().catch(env, e, stringify!($field)))
+                        }
+                    }
 
-                    if env_ptr == 0 || env_ptr != env.get_raw() as u64 {
-                        env_ptr = env.get_raw() as u64;
+                    #[track_caller]
+                    pub fn [<set_ $field:snake>](&self, env: &mut $crate::jni::JNIEnv, value: $field_ty) -> Result<(), $crate::Error>{
+                        let field_id = Self::[<__field_ $field:snake _id>](env)?;
 
-                        method_id = env.get_method_id(
-                            &class,
-                            "<init>",
-                            CTOR_SIG
-                        )?.into_raw() as u64;
+                        unsafe{
+                            $crate::export::check_argument::<$field_ty>(
+                                env,
+                                &<$field_ty as $crate::JBindingType>::to_jvalue(&value),
+                                Self::__class_cache(),
+                                stringify!($field),
+                                "value",
+                            )?;
 
-                        let cache = (env_ptr << 32) | method_id;
+                            env.set_field_unchecked(
+                                self._obj.as_obj(),
+                                field_id,
+                                <$field_ty as $crate::JBindingType>::to_jvalue_ref(&value)
+                            ).map_err(|e| Self::__class_cache().report($crate::export::catch(env, e), stringify!($field)))?;
 
-                        CACHE.store(cache, core::sync::atomic::Ordering::Relaxed);
-                    };
+                            return Ok(())
+                        }
+                    }
+                }
+            )*
 
-                    let obj = unsafe{env.new_object_unchecked(
-                        class,
-                        $crate::jni::objects::JMethodID::from_raw(method_id as _),
-                        &[
-                            $(
-                                <$ctor_arg_ty as $crate::JBindingType>::to_jvalue(unsafe{$crate::IsA::<$ctor_arg_ty>::as_ref(&$ctor_arg)})
-                            ),*
-                        ]
-                    )?};
+            $(
+                $crate::__impl_member!{
+                    [$($($static_marker)? static)?] [$([$($attr)*])*]
+                    $name $method ($($params)*) -> $ret
+                }
+            )*
+        }
 
-                    let r = env.new_global_ref(obj)?;
+        $(
+            $crate::__impl_throws!{$name $method $([$($attr)*])*}
+        )*
+    };
+}
 
-                    return Ok(Self {
-                        _obj: r,
-                    });
-                }
-            )?
+/// generates a static or instance method of a class, or resolves its method ID in `preload`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_member {
+    ([static] [$($attrs:tt)*] $class:ident $method:ident ($($arg:ident : $arg_ty:ty),*) -> $ret:ty) => {
+        $crate::__impl_static_method!{[$($attrs)*] $class $method ($($arg : $arg_ty),*) -> $ret}
+    };
+    ([] [$($attrs:tt)*] $class:ident $method:ident (&self $(, $arg:ident : $arg_ty:ty)*) -> $ret:ty) => {
+        $crate::__impl_method!{[$($attrs)*] $class $method ($($arg : $arg_ty),*) -> $ret}
+    };
+    (@preload $errors:ident $env:ident [static] $method:ident) => {
+        $crate::export::paste::paste!{
+            let id = Self::[<__static_ $method:snake _id>]($env);
+            $errors.check($env, $crate::MemberKind::StaticMethod, stringify!($method), Self::[<__STATIC_ $method:snake:upper _SIG>], id);
+        }
+    };
+    (@preload $errors:ident $env:ident [] $method:ident) => {
+        $crate::export::paste::paste!{
+            let id = Self::[<__method_ $method:snake _id>]($env);
+            $errors.check($env, $crate::MemberKind::Method, stringify!($method), Self::[<__METHOD_ $method:snake:upper _SIG>], id);
+        }
+    };
+}
 
+/// generates an instance method and its cached method ID inside the impl block of a class.
+///
+/// `#[fast]` makes the method use the raw call path, other attributes are kept on the method.
+/// with `#[throws(...)]` the method returns the error enum generated by `__impl_throws!`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_method {
+    (@attrs [$($attrs:tt)*] [$fast:tt] [$($throws:ident),*] [fast] $($rest:tt)*) => {
+        $crate::__impl_method!{@attrs [$($attrs)*] [true] [$($throws),*] $($rest)*}
+    };
+    (@attrs [$($attrs:tt)*] [$fast:tt] [$($throws:ident),*] [throws($($declared:ident),+)] $($rest:tt)*) => {
+        $crate::__impl_method!{@attrs [$($attrs)*] [$fast] [$($declared),+] $($rest)*}
+    };
+    (@attrs [$($attrs:tt)*] [$fast:tt] [$($throws:ident),*] [$($attr:tt)*] $($rest:tt)*) => {
+        $crate::__impl_method!{@attrs [$($attrs)* #[$($attr)*]] [$fast] [$($throws),*] $($rest)*}
+    };
+    (@attrs [$($attrs:tt)*] [$fast:tt] [$($throws:ident),*] ; $class:ident $method:ident ($($arg:ident : $arg_ty:ty),*) -> $ret:ty) => {
+        $crate::export::paste::paste!{
+            const [<__METHOD_ $method:snake:upper _SIG>]: &'static str = $crate::export::const_format::concatcp!(
+                "(",
+                $(
+                    <$arg_ty as $crate::JBindingType>::SIGNATURE,
+                )*
+                ")",
+                <$ret as $crate::JReturnType>::SIGNATURE
+            );
+
+            fn [<__method_ $method:snake _id>](env: &mut $crate::jni::JNIEnv) -> Result<$crate::jni::objects::JMethodID, $crate::Error>{
+                static METHOD_ID: $crate::export::IdCache<$crate::jni::objects::JMethodID> = $crate::export::IdCache::new();
+
+                Self::__class_cache().member(
+                    env,
+                    &METHOD_ID,
+                    $crate::MemberKind::Method,
+                    stringify!($method),
+                    Self::[<__METHOD_ $method:snake:upper _SIG>],
+                    |env, class, name, sig| env.get_method_id(class, name, sig),
+                )
+            }
+        }
 
-            $(
-                $crate::export::paste::paste!{
-                    pub fn [<get_ $field:snake>](&self, env: &mut $crate::jni::JNIEnv) -> Result<$field_ty, $crate::jni::errors::Error>{
-                        let class = Self::class(env)?;
+        $crate::__impl_method!{@throws [$($attrs)*] [$fast] $class $method ($($arg : $arg_ty),*) -> $ret [$($throws),*]}
+    };
+    (@throws [$($attrs:tt)*] [$fast:tt] $class:ident $method:ident ($($arg:ident : $arg_ty:ty),*) -> $ret:ty []) => {
+        $crate::__impl_method!{@fn [$($attrs)*] [pub] [] [] [$fast] $method ($($arg : $arg_ty),*) -> $ret}
+    };
+    (@throws [$($attrs:tt)*] [$fast:tt] $class:ident $method:ident ($($arg:ident : $arg_ty:ty),*) -> $ret:ty [$($throws:ident),+]) => {
+        $crate::__impl_method!{@fn [] [] [__] [_unmapped] [$fast] $method ($($arg : $arg_ty),*) -> $ret}
 
-                        static CACHE: ::core::sync::atomic::AtomicU64 = ::core::sync::atomic::AtomicU64::new(0);
+        $crate::export::paste::paste!{
+            $($attrs)*
+            #[track_caller]
+            pub fn [<$method:snake>](&self, env: &mut $crate::jni::JNIEnv $(, $arg : impl $crate::IsA<$arg_ty>)*) -> Result<$ret, [<$class $method:camel Error>]>{
+                self.[<__ $method:snake _unmapped>](env $(, $arg)*)
+                    .map_err(|e| [<$class $method:camel Error>]::from_error(env, e))
+            }
+        }
+    };
+    (@fn [$($attrs:tt)*] [$($vis:tt)*] [$($prefix:tt)*] [$($suffix:tt)*] [$fast:tt] $method:ident ($($arg:ident : $arg_ty:ty),*) -> $ret:ty) => {
+        $crate::export::paste::paste!{
+            $($attrs)*
+            #[track_caller]
+            $($vis)* fn [<$($prefix)* $method:snake $($suffix)*>](&self, env: &mut $crate::jni::JNIEnv $(, $arg : impl $crate::IsA<$arg_ty>)*) -> Result<$ret, $crate::Error>{
+                let method_id = Self::[<__method_ $method:snake _id>](env)?;
 
-                        let id = CACHE.load(::core::sync::atomic::Ordering::Relaxed);
+                $(
+                    unsafe {
+                        $crate::export::check_argument::<$arg_ty>(
+                            env,
+                            &<$arg_ty as $crate::JBindingType>::to_jvalue($crate::IsA::<$arg_ty>::as_ref(&$arg)),
+                            Self::__class_cache(),
+                            stringify!($method),
+                            stringify!($arg),
+                        )?;
+                    }
+                )*
 
-                        let mut env_ptr = id >> 32;
-                        let mut field_id = id & 0xFFFFFFFF;
+                if $fast || Self::__FAST_CALLS {
+                    unsafe{
+                        let args: &[$crate::jni::sys::jvalue] = &[
+                            $(
+                                <$arg_ty as $crate::JBindingType>::to_jvalue($crate::IsA::<$arg_ty>::as_ref(&$arg))
+                            ),*
+                        ];
+                        let r = <$ret as $crate::JReturnType>::call_method_a(env, self._obj.as_obj().as_raw(), method_id.into_raw(), args.as_ptr());
+                        $crate::export::check_exception(env).map_err(|e| Self::__class_cache().report(e, stringify!($method)))?;
+                        $crate::export::check_return::<$ret>(env, &r, Self::__class_cache(), stringify!($method))?;
 
-                        if env_ptr == 0 || env_ptr != env.get_raw() as u64 {
-                            env_ptr = env.get_raw() as u64;
+                        return <$ret as $crate::JReturnType>::from_jvalue(env, r)
+                            .map_err(|e| Self::__class_cache().catch(env, e, stringify!($method)))
+                    }
+                }
 
-                            field_id = env.get_field_id(
-                                &class,
-                                stringify!(u),
-                                <i8 as $crate::JReturnType>::SIGNATURE
-                            )?.into_raw() as u64;
+                unsafe{
+                    let r = env.call_method_unchecked(
+                        self._obj.as_obj(),
+                        method_id,
+                        <$ret as $crate::JReturnType>::JNI_RETURN_TY,
+                        &[
+                            $(
+                                <$arg_ty as $crate::JBindingType>::to_jvalue(unsafe{$crate::IsA::<$arg_ty>::as_ref(&$arg)})
+                            ),*
+                        ]
+                    ).map_err(|e| Self::__class_cache().report($crate::export::catch(env, e), stringify!($method)))?.as_jni();
+                    $crate::export::check_return::<$ret>(env, &r, Self::__class_cache(), stringify!($method))?;
 
-                            let cache = (env_ptr << 32) | field_id;
+                    return <$ret as $crate::JReturnType>::from_jvalue(env, r)
+                        .map_err(|e| Self::__class_cache().catch(env, e, stringify!($method)))
+                };
+            }
+        }
+    };
+    ([$($attrs:tt)*] $class:ident $method:ident ($($arg:ident : $arg_ty:ty),*) -> $ret:ty) => {
+        $crate::__impl_method!{@attrs [] [false] [] $($attrs)* ; $class $method ($($arg : $arg_ty),*) -> $ret}
+    };
+}
 
-                            CACHE.store(cache, ::core::sync::atomic::Ordering::Relaxed);
-                        };
+/// generates a static method and its cached method ID inside the impl block of a class.
+///
+/// attributes are handled as for `__impl_method!`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_static_method {
+    (@attrs [$($attrs:tt)*] [$fast:tt] [$($throws:ident),*] [fast] $($rest:tt)*) => {
+        $crate::__impl_static_method!{@attrs [$($attrs)*] [true] [$($throws),*] $($rest)*}
+    };
+    (@attrs [$($attrs:tt)*] [$fast:tt] [$($throws:ident),*] [throws($($declared:ident),+)] $($rest:tt)*) => {
+        $crate::__impl_static_method!{@attrs [$($attrs)*] [$fast] [$($declared),+] $($rest)*}
+    };
+    (@attrs [$($attrs:tt)*] [$fast:tt] [$($throws:ident),*] [$($attr:tt)*] $($rest:tt)*) => {
+        $crate::__impl_static_method!{@attrs [$($attrs)* #[$($attr)*]] [$fast] [$($throws),*] $($rest)*}
+    };
+    (@attrs [$($attrs:tt)*] [$fast:tt] [$($throws:ident),*] ; $class:ident $static_method:ident ($($static_arg:ident : $static_arg_ty:ty),*) -> $static_ret:ty) => {
+        $crate::export::paste::paste!{
+            const [<__STATIC_ $static_method:snake:upper _SIG>]: &'static str = $crate::export::const_format::concatcp!(
+                "(",
+                $(
+                    <$static_arg_ty as $crate::JBindingType>::SIGNATURE,
+                )*
+                ")",
+                <$static_ret as $crate::JReturnType>::SIGNATURE
+            );
+
+            fn [<__static_ $static_method:snake _id>](env: &mut $crate::jni::JNIEnv) -> Result<$crate::jni::objects::JStaticMethodID, $crate::Error>{
+                static METHOD_ID: $crate::export::IdCache<$crate::jni::objects::JStaticMethodID> = $crate::export::IdCache::new();
+
+                Self::__class_cache().member(
+                    env,
+                    &METHOD_ID,
+                    $crate::MemberKind::StaticMethod,
+                    stringify!($static_method),
+                    Self::[<__STATIC_ $static_method:snake:upper _SIG>],
+                    |env, class, name, sig| env.get_static_method_id(class, name, sig),
+                )
+            }
+        }
 
-                        unsafe{
-                            let b = env.get_field_unchecked(
-                                self._obj.as_obj(),
-                                $crate::jni::objects::JFieldID::from_raw(field_id as _),
-                                <$field_ty as $crate::JReturnType>::JNI_RETURN_TY
-                            )?;
+        $crate::__impl_static_method!{@throws [$($attrs)*] [$fast] $class $static_method ($($static_arg : $static_arg_ty),*) -> $static_ret [$($throws),*]}
+    };
+    (@throws [$($attrs:tt)*] [$fast:tt] $class:ident $static_method:ident ($($static_arg:ident : $static_arg_ty:ty),*) -> $static_ret:ty []) => {
+        $crate::__impl_static_method!{@fn [$($attrs)*] [pub] [] [] [$fast] $static_method ($($static_arg : $static_arg_ty),*) -> $static_ret}
+    };
+    (@throws [$($attrs:tt)*] [$fast:tt] $class:ident $static_method:ident ($($static_arg:ident : $static_arg_ty:ty),*) -> $static_ret:ty [$($throws:ident),+]) => {
+        $crate::__impl_static_method!{@fn [] [] [__] [_unmapped] [$fast] $static_method ($($static_arg : $static_arg_ty),*) -> $static_ret}
 
-                            return Ok(<$field_ty as $crate::JReturnType>::from_jvalue(env, b.as_jni()))
-                        }
+        $crate::export::paste::paste!{
+            $($attrs)*
+            #[track_caller]
+            pub fn [<$static_method:snake>](env: &mut $crate::jni::JNIEnv $(, $static_arg : impl $crate::IsA<$static_arg_ty>)*) -> Result<$static_ret, [<$class $static_method:camel Error>]>{
+                Self::[<__ $static_method:snake _unmapped>](env $(, $static_arg)*)
+                    .map_err(|e| [<$class $static_method:camel Error>]::from_error(env, e))
+            }
+        }
+    };
+    (@fn [$($attrs:tt)*] [$($vis:tt)*] [$($prefix:tt)*] [$($suffix:tt)*] [$fast:tt] $static_method:ident ($($static_arg:ident : $static_arg_ty:ty),*) -> $static_ret:ty) => {
+        $crate::export::paste::paste!{
+            $($attrs)*
+            #[track_caller]
+            $($vis)* fn [<$($prefix)* $static_method:snake $($suffix)*>](env: &mut $crate::jni::JNIEnv $(, $static_arg : impl $crate::IsA<$static_arg_ty>)*) -> Result<$static_ret, $crate::Error>{
+                let class = Self::class(env)?;
+                let method_id = Self::[<__static_ $static_method:snake _id>](env)?;
+
+                $(
+                    unsafe {
+                        $crate::export::check_argument::<$static_arg_ty>(
+                            env,
+                            &<$static_arg_ty as $crate::JBindingType>::to_jvalue($crate::IsA::<$static_arg_ty>::as_ref(&$static_arg)),
+                            Self::__class_cache(),
+                            stringify!($static_method),
+                            stringify!($static_arg),
+                        )?;
                     }
+                )*
 
-                    pub fn [<set_ $field:snake>](&self, env: &mut $crate::jni::JNIEnv, value: $field_ty) -> Result<(), $crate::jni::errors::Error>{
-                        let class = Self::class(env)?;
+                if $fast || Self::__FAST_CALLS {
+                    unsafe{
+                        let args: &[$crate::jni::sys::jvalue] = &[
+                            $(
+                                <$static_arg_ty as $crate::JBindingType>::to_jvalue($crate::IsA::<$static_arg_ty>::as_ref(&$static_arg))
+                            ),*
+                        ];
+                        let re = <$static_ret as $crate::JReturnType>::call_static_method_a(env, class.as_raw(), method_id.into_raw(), args.as_ptr());
+                        $crate::export::check_exception(env).map_err(|e| Self::__class_cache().report(e, stringify!($static_method)))?;
+                        $crate::export::check_return::<$static_ret>(env, &re, Self::__class_cache(), stringify!($static_method))?;
 
-                        static CACHE: ::core::sync::atomic::AtomicU64 = ::core::sync::atomic::AtomicU64::new(0);
+                        return <$static_ret as $crate::JReturnType>::from_jvalue(env, re)
+                            .map_err(|e| Self::__class_cache().catch(env, e, stringify!($static_method)))
+                    }
+                }
 
-                        let id = CACHE.load(::core::sync::atomic::Ordering::Relaxed);
+                unsafe{
+                    let re = env.call_static_method_unchecked(
+                        class,
+                        method_id,
+                        <$static_ret as $crate::JReturnType>::JNI_RETURN_TY,
+                        &[
+                            $(
+                                <$static_arg_ty as $crate::JBindingType>::to_jvalue(unsafe{$crate::IsA::<$static_arg_ty>::as_ref(&$static_arg)})
+                            ),*
+                        ]
+                    ).map_err(|e| Self::__class_cache().report($crate::export::catch(env, e), stringify!($static_method)))?.as_jni();
+                    $crate::export::check_return::<$static_ret>(env, &re, Self::__class_cache(), stringify!($static_method))?;
 
-                        let mut env_ptr = id >> 32;
-                        let mut field_id = id & 0xFFFFFFFF;
+                    return <$static_ret as $crate::JReturnType>::from_jvalue(env, re)
+                        .map_err(|e| Self::__class_cache().catch(env, e, stringify!($static_method)))
+                };
+            }
+        }
+    };
+    ([$($attrs:tt)*] $class:ident $static_method:ident ($($static_arg:ident : $static_arg_ty:ty),*) -> $static_ret:ty) => {
+        $crate::__impl_static_method!{@attrs [] [false] [] $($attrs)* ; $class $static_method ($($static_arg : $static_arg_ty),*) -> $static_ret}
+    };
+}
 
-                        if env_ptr == 0 || env_ptr != env.get_raw() as u64 {
-                            env_ptr = env.get_raw() as u64;
+/// generates the error enum of a method with a `#[throws(...)]` attribute, nothing without one.
+///
+/// a thrown exception becomes the variant of the most specific declared class it is an instance of.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_throws {
+    ($class:ident $method:ident) => {};
+    ($class:ident $method:ident [throws($($throws:ident),+)] $($rest:tt)*) => {
+        $crate::export::paste::paste!{
+            #[doc = concat!("the exceptions declared by `", stringify!($class), "::", stringify!($method), "`.")]
+            #[derive(Debug)]
+            pub enum [<$class $method:camel Error>] {
+                $(
+                    $throws($throws),
+                )+
+                /// any other error, including exceptions that were not declared.
+                Other($crate::Error),
+            }
 
-                            field_id = env.get_field_id(
-                                &class,
-                                stringify!(u),
-                                <$field_ty as $crate::JReturnType>::SIGNATURE
-                            )?.into_raw() as u64;
+            impl [<$class $method:camel Error>] {
+                /// converts an error returned by the call,
+                /// matching a java exception against the declared classes.
+                pub fn from_error(env: &mut $crate::jni::JNIEnv, error: $crate::Error) -> Self {
+                    let $crate::Error::JavaException(exception) = &error else {
+                        return Self::Other(error);
+                    };
+                    let thrown = $crate::JObjectType::object_ref(&exception.throwable);
 
-                            let cache = (env_ptr << 32) | field_id;
+                    let best = $crate::export::suspend_exception(env, |env| {
+                        let mut best: Option<(&$crate::jni::objects::JClass, fn($crate::ObjectRef) -> Self)> = None;
+                        $(
+                            if let Ok(class) = <$throws as $crate::JObjectType>::class(env) {
+                                if $crate::export::is_more_specific(env, thrown.as_obj(), class, best.map(|(c, _)| c)) {
+                                    best = Some((class, |obj| Self::$throws(unsafe { <$throws as $crate::JObjectType>::from_object_ref(obj) })));
+                                }
+                            }
+                        )+
+                        best
+                    });
 
-                            CACHE.store(cache, ::core::sync::atomic::Ordering::Relaxed);
-                        };
+                    match best {
+                        Some((_, variant)) => variant(::core::clone::Clone::clone(thrown)),
+                        None => Self::Other(error),
+                    }
+                }
+            }
 
-                        unsafe{
-                            env.set_field_unchecked(
-                                self._obj.as_obj(),
-                                $crate::jni::objects::JFieldID::from_raw(field_id as _),
-                                <$field_ty as $crate::JBindingType>::to_jvalue_ref(&value)
-                            )?;
+            impl ::core::fmt::Display for [<$class $method:camel Error>] {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    match self {
+                        $(
+                            Self::$throws(e) => ::core::fmt::Display::fmt(e, f),
+                        )+
+                        Self::Other(e) => ::core::fmt::Display::fmt(e, f),
+                    }
+                }
+            }
 
-                            return Ok(())
-                        }
+            impl ::std::error::Error for [<$class $method:camel Error>] {
+                fn source(&self) -> Option<&(dyn ::std::error::Error + 'static)> {
+                    match self {
+                        Self::Other(e) => Some(e),
+                        _ => None,
                     }
                 }
-            )*
+            }
+        }
+    };
+    ($class:ident $method:ident [$($attr:tt)*] $($rest:tt)*) => {
+        $crate::__impl_throws!{$class $method $($rest)*}
+    };
+}
 
-            $(
-                $crate::export::paste::paste!{
-                    pub fn [<$static_method:snake>](env: &mut $crate::jni::JNIEnv $(, $static_arg : impl $crate::IsA<$static_arg_ty>)*) -> Result<$static_ret, $crate::jni::errors::Error>{
-                        let class = Self::class(env)?;
+/// whether a class uses the raw call path for all its methods, from its `calls` option.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __fast_calls {
+    () => { false };
+    (default) => { false };
+    (fast) => { true };
+}
 
-                        const METHOD_SIG: &str = $crate::export::const_format::concatcp!(
-                            "(",
-                            $(
-                                <$static_arg_ty as $crate::JBindingType>::SIGNATURE,
-                            )*
-                            ")",
-                            <$static_ret as $crate::JReturnType>::SIGNATURE
-                        );
+/// the caching strategy of a binding, from its `cache` option.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cache_strategy {
+    () => { $crate::CacheStrategy::DEFAULT };
+    (lazy) => { $crate::CacheStrategy::Lazy };
+    (eager) => { $crate::CacheStrategy::Eager };
+    (none) => { $crate::CacheStrategy::None };
+}
 
-                        static CACHE: ::core::sync::atomic::AtomicU64 = ::core::sync::atomic::AtomicU64::new(0);
+/// whether looking up a class may run its static initializer, from its `initialize` option.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __initialize_on_lookup {
+    () => { true };
+    (lookup) => { true };
+    (explicit) => { false };
+}
 
-                        let id = CACHE.load(::core::sync::atomic::Ordering::Relaxed);
+/// the descriptor of a constructor, from its parameter list.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __constructor_signature {
+    (($($ctor_arg:ident : $ctor_arg_ty:ty),*)) => {
+        $crate::export::const_format::concatcp!(
+            "(",
+            $(
+                <$ctor_arg_ty as $crate::JBindingType>::SIGNATURE,
+            )*
+            ")V"
+        )
+    };
+}
 
-                        let mut env_ptr = id >> 32;
-                        let mut method_id = id & 0xFFFFFFFF;
+/// generates `new` and the cached constructor ID inside the impl block of a class.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_constructor {
+    (($($ctor_arg:ident : $ctor_arg_ty:ty),*)) => {
+        fn __ctor_id(env: &mut $crate::jni::JNIEnv) -> Result<$crate::jni::objects::JMethodID, $crate::Error> {
+            static METHOD_ID: $crate::export::IdCache<$crate::jni::objects::JMethodID> = $crate::export::IdCache::new();
+
+            Self::__class_cache().member(
+                env,
+                &METHOD_ID,
+                $crate::MemberKind::Constructor,
+                "<init>",
+                $crate::__constructor_signature!(($($ctor_arg : $ctor_arg_ty),*)),
+                |env, class, name, sig| env.get_method_id(class, name, sig),
+            )
+        }
 
-                        if env_ptr == 0 || env_ptr != env.get_raw() as u64 {
-                            env_ptr = env.get_raw() as u64;
+        #[track_caller]
+        pub fn new(env: &mut $crate::jni::JNIEnv $(, $ctor_arg : impl $crate::IsA<$ctor_arg_ty>)*) -> Result<Self, $crate::Error> {
+            let class = Self::class(env)?;
+            let method_id = Self::__ctor_id(env)?;
 
-                            method_id = env.get_static_method_id(
-                                &class,
-                                stringify!($static_method),
-                                METHOD_SIG
-                            )?.into_raw() as u64;
+            $(
+                unsafe {
+                    $crate::export::check_argument::<$ctor_arg_ty>(
+                        env,
+                        &<$ctor_arg_ty as $crate::JBindingType>::to_jvalue($crate::IsA::<$ctor_arg_ty>::as_ref(&$ctor_arg)),
+                        Self::__class_cache(),
+                        "<init>",
+                        stringify!($ctor_arg),
+                    )?;
+                }
+            )*
 
-                            let cache = (env_ptr << 32) | method_id;
+            let obj = unsafe{env.new_object_unchecked(
+                class,
+                method_id,
+                &[
+                    $(
+                        <$ctor_arg_ty as $crate::JBindingType>::to_jvalue(unsafe{$crate::IsA::<$ctor_arg_ty>::as_ref(&$ctor_arg)})
+                    ),*
+                ]
+            )}.map_err(|e| Self::__class_cache().report($crate::export::catch_null(env, e, <Self as $crate::JBindingType>::NAME, "<init>"), "<init>"))?;
+
+            return Ok(Self {
+                _obj: unsafe { $crate::export::promote_local::<Self>(env, obj.into_raw()) }
+                    .map_err(|e| Self::__class_cache().catch(env, e, "<init>"))?,
+            });
+        }
+    };
+}
 
-                            CACHE.store(cache, core::sync::atomic::Ordering::Relaxed);
-                        };
+/// implements `JObjectType` for a binding.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_object {
+    ($name:ident) => {
+        unsafe impl $crate::JObjectType for $name {
+            fn class(env: &mut $crate::jni::JNIEnv) -> Result<&'static $crate::jni::objects::JClass<'static>, $crate::Error> {
+                $name::class(env)
+            }
 
-                        unsafe{
-                            let re = env.call_static_method_unchecked(
-                                &class,
-                                $crate::jni::objects::JStaticMethodID::from_raw(method_id as _),
-                                <$static_ret as $crate::JReturnType>::JNI_RETURN_TY,
-                                &[
-                                    $(
-                                        <$static_arg_ty as $crate::JBindingType>::to_jvalue(unsafe{$crate::IsA::<$static_arg_ty>::as_ref(&$static_arg)})
-                                    ),*
-                                ]
-                            )?;
+            fn initialize(env: &mut $crate::jni::JNIEnv) -> Result<(), $crate::Error> {
+                $name::__class_cache().initialize(env)
+            }
 
-                            return Ok(<$static_ret as $crate::JReturnType>::from_jvalue(env, re.as_jni()))
-                        };
-                    }
-                }
-            )*
+            fn preload(env: &mut $crate::jni::JNIEnv) -> Result<(), $crate::BindingErrors> {
+                $name::__preload(env)
+            }
 
-            $(
-                $crate::export::paste::paste!{
-                    $(#[doc=$doc])*
-                    pub fn [<$method:snake>](&self, env: &mut $crate::jni::JNIEnv $(, $arg : impl $crate::IsA<$arg_ty>)*) -> Result<$ret, $crate::jni::errors::Error>{
-                        let class = Self::class(env)?;
+            fn set_class_loader(env: &$crate::jni::JNIEnv, loader: &$crate::jni::objects::JObject) -> Result<(), $crate::Error> {
+                $name::__class_cache().set_loader(env, loader)
+            }
 
-                        const METHOD_SIG: &str = $crate::export::const_format::concatcp!(
-                            "(",
-                            $(
-                                <$arg_ty as $crate::JBindingType>::SIGNATURE,
-                            )*
-                            ")",
-                            <$ret as $crate::JReturnType>::SIGNATURE
-                        );
+            fn clear_class_loader() {
+                $name::__class_cache().clear_loader()
+            }
 
-                        static CACHE: core::sync::atomic::AtomicU64 = core::sync::atomic::AtomicU64::new(0);
+            #[track_caller]
+            unsafe fn alloc_uninit(env: &mut $crate::jni::JNIEnv) -> Result<Self, $crate::Error> {
+                let class = $name::class(env)?;
+                let obj = env
+                    .alloc_object(class)
+                    .map_err(|e| $name::__class_cache().report($crate::export::catch_null(env, e, <Self as $crate::JBindingType>::NAME, "AllocObject"), "AllocObject"))?;
+
+                Ok(Self {
+                    _obj: $crate::export::promote_local::<Self>(env, obj.into_raw())
+                        .map_err(|e| $name::__class_cache().catch(env, e, "AllocObject"))?,
+                })
+            }
 
-                        let id = CACHE.load(core::sync::atomic::Ordering::Relaxed);
+            fn object_ref(&self) -> &$crate::ObjectRef {
+                &self._obj
+            }
 
-                        let mut env_ptr = id >> 32;
-                        let mut method_id = id & 0xFFFFFFFF;
+            unsafe fn from_object_ref(obj: $crate::ObjectRef) -> Self {
+                Self { _obj: obj }
+            }
 
-                        if env_ptr == 0 || env_ptr != env.get_raw() as u64 {
-                            env_ptr = env.get_raw() as u64;
+            fn class_cache() -> &'static $crate::export::ClassCache {
+                $name::__class_cache()
+            }
+        }
+    };
+}
 
-                            method_id = env.get_method_id(
-                                &class,
-                                stringify!($method),
-                                METHOD_SIG
-                            )?.into_raw() as u64;
+/// implements `Display` with `toString()` and `Debug` with the runtime class name and identity hash.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_fmt {
+    ($name:ident) => {
+        impl ::core::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                $crate::export::fmt_display(stringify!($name), self._obj.as_obj(), f)
+            }
+        }
 
-                            let cache = (env_ptr << 32) | method_id;
+        impl ::core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                $crate::export::fmt_debug(stringify!($name), self._obj.as_obj(), f)
+            }
+        }
+    };
+}
 
-                            CACHE.store(cache, core::sync::atomic::Ordering::Relaxed);
-                        };
+/// implements `PartialEq`, `Eq` and `Hash` for a binding.
+///
+/// `identity` compares with `IsSameObject` and hashes with `System.identityHashCode`,
+/// `java` uses `equals()` and `hashCode()`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_equality {
+    ($name:ident, identity) => {
+        impl ::core::cmp::PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                $crate::export::identity_eq(self._obj.as_obj(), other._obj.as_obj())
+            }
+        }
 
-                        unsafe{
-                            let r = env.call_method_unchecked(
-                                self._obj.as_obj(),
-                                $crate::jni::objects::JMethodID::from_raw(method_id as _),
-                                <$ret as $crate::JReturnType>::JNI_RETURN_TY,
-                                &[
-                                    $(
-                                        <$arg_ty as $crate::JBindingType>::to_jvalue(unsafe{$crate::IsA::<$arg_ty>::as_ref(&$arg)})
-                                    ),*
-                                ]
-                            )?;
+        impl ::core::cmp::Eq for $name {}
 
-                            return Ok(<$ret as $crate::JReturnType>::from_jvalue(env, r.as_jni()))
-                        };
-                    }
-                }
-            )*
+        impl ::core::hash::Hash for $name {
+            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                ::core::hash::Hash::hash(&$crate::export::identity_hash_code(self._obj.as_obj()), state)
+            }
+        }
+    };
+    ($name:ident, java) => {
+        impl ::core::cmp::PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                $crate::export::java_equals(self._obj.as_obj(), other._obj.as_obj())
+            }
         }
 
+        impl ::core::cmp::Eq for $name {}
+
+        impl ::core::hash::Hash for $name {
+            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                ::core::hash::Hash::hash(&$crate::export::java_hash_code(self._obj.as_obj()), state)
+            }
+        }
     };
 }
 
@@ -463,15 +1057,25 @@ macro_rules! import_interface {
         $sig: expr;
         $name: ident;
         $(implements $($parent_interface: ty),+;)?
+        $(equality $equality:ident;)?
+        $(calls $calls:ident;)?
+        $(cache $cache:ident;)?
         $(
-            $(#[doc=$doc:expr])*
+            $(#[$($attr:tt)*])*
             fn $method:ident (&self $(, $arg:ident : $arg_ty:ty)*) -> $ret:ty;
         )*
     ) => {
         #[repr(transparent)]
-        #[derive(Debug, Clone)]
         pub struct $name{
-            _obj: $crate::jni::objects::GlobalRef,
+            _obj: $crate::ObjectRef,
+        }
+
+        impl ::core::clone::Clone for $name {
+            fn clone(&self) -> Self {
+                Self {
+                    _obj: ::core::clone::Clone::clone(&self._obj),
+                }
+            }
         }
 
         unsafe impl $crate::JBindingType for $name {
@@ -484,7 +1088,7 @@ macro_rules! import_interface {
                 }
             }
 
-            unsafe fn to_jvalue_ref<'obj_ref>(&'obj_ref self) -> $crate::jni::objects::JValue<'_, 'obj_ref>{
+            unsafe fn to_jvalue_ref<'obj_ref>(&'obj_ref self) -> $crate::jni::objects::JValue<'obj_ref, 'obj_ref>{
                 $crate::jni::objects::JValue::Object(
                     self._obj.as_obj()
                 )
@@ -496,12 +1100,15 @@ macro_rules! import_interface {
             const NAME: &'static str = <Self as $crate::JBindingType>::NAME;
             const JNI_RETURN_TY: jni::signature::ReturnType = jni::signature::ReturnType::Object;
 
-            unsafe fn from_jvalue(env: &mut$crate::JNIEnv, value: $crate::jni::sys::jvalue) -> Self {
-                let o = $crate::jni::objects::JObject::from_raw(value.l);
-                let r = env.new_global_ref(o).expect("failed to create global ref");
-                Self {
-                    _obj: r,
-                }
+            unsafe fn from_jvalue(env: &mut$crate::JNIEnv, value: $crate::jni::sys::jvalue) -> Result<Self, $crate::Error> {
+                Ok(Self {
+                    _obj: $crate::export::promote_local::<Self>(env, value.l)?,
+                })
+            }
+
+            unsafe fn type_mismatch(env: &mut $crate::JNIEnv, value: &$crate::jni::sys::jvalue) -> Result<Option<::std::string::String>, $crate::Error> {
+                let class = <Self as $crate::JObjectType>::class(env)?;
+                $crate::export::type_mismatch(env, value.l, class)
             }
         }
         
@@ -517,6 +1124,22 @@ macro_rules! import_interface {
             }
         }
 
+        $crate::__impl_fmt!($name);
+        $crate::__impl_object!($name);
+
+        $crate::export::inventory::submit! {
+            $crate::RegisteredClass::new(
+                concat!(module_path!(), "::", stringify!($name)),
+                $sig,
+                concat!("L", $sig, ";"),
+                <$name as $crate::JObjectType>::preload
+            )
+        }
+
+        $(
+            $crate::__impl_equality!($name, $equality);
+        )?
+
         $(
             $(
                 impl ::core::convert::AsRef<$parent_interface> for $name{
@@ -534,6 +1157,12 @@ macro_rules! import_interface {
                         }
                     }
                 }
+
+                impl From<&$name> for $parent_interface{
+                    fn from(value: &$name) -> $parent_interface{
+                        $crate::JObjectType::upcast(value)
+                    }
+                }
     
                 unsafe impl $crate::IsA<$parent_interface> for $name{
                     unsafe fn as_ref(&self) -> &$parent_class{
@@ -550,41 +1179,52 @@ macro_rules! import_interface {
         )?
 
         $crate::export::paste::paste!{
+            #[allow(unused)]
             impl $name{
-                $(
-                    $(#[doc=$doc])*
-                    pub fn [<$method:snake>](&self, env: &mut $crate::jni::JNIEnv, $($arg: impl $crate::IsA<$arg_ty>),*) -> ::core::result::Result<$ret, $crate::jni::errors::Error>{
+                /// whether every method of this interface uses the raw call path.
+                const __FAST_CALLS: bool = $crate::__fast_calls!($($calls)?);
 
-                        const METHOD_SIG: &str = $crate::export::const_format::concatcp!(
-                            "(",
-                            $(
-                                <$arg_ty as $crate::JBindingType>::SIGNATURE,
-                            )*
-                            ")",
-                            <$ret as $crate::JReturnType>::SIGNATURE
-                        );
+                fn __class_cache() -> &'static $crate::export::ClassCache {
+                    static CLASS: $crate::export::ClassCache = $crate::export::ClassCache::new(stringify!($name), $sig, $crate::__cache_strategy!($($cache)?), true, <$name as $crate::JObjectType>::preload);
+                    &CLASS
+                }
 
-                        let class = env.get_object_class(self._obj.as_obj())?;
-                        let method_id = env.get_method_id(class, stringify!($method), METHOD_SIG)?;
+                #[allow(dead_code)]
+                fn class(env: &mut $crate::jni::JNIEnv) -> Result<&'static $crate::jni::objects::JClass<'static>, $crate::Error>{
+                    Self::__class_cache().get(env)
+                }
 
-                        unsafe{
-                            let r = env.call_method_unchecked(
-                                self._obj.as_obj(),
-                                method_id,
-                                <$ret as $crate::JReturnType>::JNI_RETURN_TY,
-                                &[
-                                    $(
-                                        <$arg_ty as $crate::JBindingType>::to_jvalue(unsafe{$crate::IsA::<$arg_ty>::as_ref(&$arg)})
-                                    ),*
-                                ]
-                            )?;
+                /// see `JObjectType::preload`.
+                fn __preload(env: &mut $crate::jni::JNIEnv) -> Result<(), $crate::BindingErrors> {
+                    let mut errors = $crate::BindingErrors::new(<Self as $crate::JBindingType>::NAME);
 
-                            return Ok(<$ret as $crate::JReturnType>::from_jvalue(env, r.as_jni()))
-                        };
+                    let class = Self::class(env);
+                    if !errors.check(env, $crate::MemberKind::Class, <Self as $crate::JBindingType>::NAME, <Self as $crate::JBindingType>::SIGNATURE, class) {
+                        return errors.into_result(env);
                     }
-                )*
+
+                    $(
+                        let id = Self::[<__method_ $method:snake _id>](env);
+                        errors.check(env, $crate::MemberKind::Method, stringify!($method), Self::[<__METHOD_ $method:snake:upper _SIG>], id);
+                    )*
+
+                    errors.into_result(env)
+                }
             }
         }
-        
+
+        #[allow(unused)]
+        impl $name {
+            $(
+                $crate::__impl_method!{
+                    [$([$($attr)*])*]
+                    $name $method ($($arg : $arg_ty),*) -> $ret
+                }
+            )*
+        }
+
+        $(
+            $crate::__impl_throws!{$name $method $([$($attr)*])*}
+        )*
     };
 }
\ No newline at end of file
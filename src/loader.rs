@@ -0,0 +1,197 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+use jni::objects::{JClass, JObject, JValue};
+use jni::JNIEnv;
+
+use crate::reference::Global;
+use crate::Error;
+
+/// a class loader used to look up bound classes.
+///
+/// loaders are kept until the vm shuts down, along with the classes cached for them.
+pub(crate) struct Loader {
+    /// unique for every loader set, used to key the caches.
+    pub(crate) id: u64,
+    obj: Global,
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// every loader set so far, so that setting one again reuses its cached classes.
+static LOADERS: Mutex<Vec<Arc<Loader>>> = Mutex::new(Vec::new());
+
+impl Loader {
+    pub(crate) fn new(env: &JNIEnv, loader: &JObject) -> Result<Arc<Self>, Error> {
+        let mut loaders = LOADERS.lock().unwrap_or_else(PoisonError::into_inner);
+
+        for known in loaders.iter() {
+            if env.is_same_object(known.as_obj(), loader)? {
+                return Ok(known.clone());
+            }
+        }
+
+        let new = Arc::new(Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            obj: Global::new(env, loader)?,
+        });
+        loaders.push(new.clone());
+
+        Ok(new)
+    }
+
+    /// `loader.loadClass(name)` with the dotted form of the binary name.
+    pub(crate) fn load_class<'local>(
+        &self,
+        env: &mut JNIEnv<'local>,
+        name: &str,
+    ) -> Result<JClass<'local>, jni::errors::Error> {
+        let dotted = env.new_string(name.replace('/', "."))?;
+
+        let class = env
+            .call_method(
+                self.obj.as_obj(),
+                "loadClass",
+                "(Ljava/lang/String;)Ljava/lang/Class;",
+                &[JValue::Object(&dotted)],
+            )
+            .and_then(|v| v.l());
+
+        env.delete_local_ref(dotted)?;
+
+        Ok(JClass::from(class?))
+    }
+
+    pub(crate) fn as_obj(&self) -> &JObject<'static> {
+        self.obj.as_obj()
+    }
+}
+
+/// `Class.forName(name, initialize, loader)` with the dotted form of the binary name.
+///
+/// a null `loader` stands for the context class loader of the current thread,
+/// which sees the classes of the application like `FindClass` does from a native method.
+pub(crate) fn for_name<'local>(
+    env: &mut JNIEnv<'local>,
+    name: &str,
+    initialize: bool,
+    loader: &JObject,
+) -> Result<JClass<'local>, jni::errors::Error> {
+    let context = if loader.is_null() {
+        context_class_loader(env)?
+    } else {
+        JObject::null()
+    };
+    let loader = if loader.is_null() { &context } else { loader };
+
+    let dotted = env.new_string(name.replace('/', "."))?;
+
+    let class = env
+        .call_static_method(
+            "java/lang/Class",
+            "forName",
+            "(Ljava/lang/String;ZLjava/lang/ClassLoader;)Ljava/lang/Class;",
+            &[
+                JValue::Object(&dotted),
+                JValue::Bool(initialize.into()),
+                JValue::Object(loader),
+            ],
+        )
+        .and_then(|v| v.l());
+
+    env.delete_local_ref(dotted)?;
+    env.delete_local_ref(context)?;
+
+    Ok(JClass::from(class?))
+}
+
+/// `Thread.currentThread().getContextClassLoader()`, null for the bootstrap class loader.
+fn context_class_loader<'local>(env: &mut JNIEnv<'local>) -> Result<JObject<'local>, jni::errors::Error> {
+    let thread = env
+        .call_static_method("java/lang/Thread", "currentThread", "()Ljava/lang/Thread;", &[])
+        .and_then(|v| v.l())?;
+
+    let loader = env
+        .call_method(&thread, "getContextClassLoader", "()Ljava/lang/ClassLoader;", &[])
+        .and_then(|v| v.l());
+    env.delete_local_ref(thread)?;
+
+    loader
+}
+
+static GLOBAL_LOADER: RwLock<Option<Arc<Loader>>> = RwLock::new(None);
+static GLOBAL_LOADER_ID: AtomicU64 = AtomicU64::new(0);
+
+/// sets the class loader used by every binding that has no class loader of its own.
+///
+/// bindings look up their class with `loader.loadClass` instead of `FindClass`,
+/// which on native threads only sees the system class loader.
+/// a null `loader` stands for the bootstrap loader and goes back to `FindClass`.
+pub fn set_class_loader(env: &JNIEnv, loader: &JObject) -> Result<(), Error> {
+    if loader.is_null() {
+        clear_class_loader();
+        return Ok(());
+    }
+
+    let loader = Loader::new(env, loader)?;
+    let id = loader.id;
+
+    *GLOBAL_LOADER.write().unwrap_or_else(PoisonError::into_inner) = Some(loader);
+    GLOBAL_LOADER_ID.store(id, Ordering::Release);
+
+    Ok(())
+}
+
+/// sets the global class loader to the one that loaded `obj`,
+/// or the one that loaded the class `obj` is an instance of.
+///
+/// useful in `JNI_OnLoad` or with a context object handed over from java.
+pub fn set_class_loader_from(env: &mut JNIEnv, obj: &JObject) -> Result<(), Error> {
+    crate::vm::ensure_running()?;
+
+    let loader = class_loader_of(env, obj).map_err(|e| crate::exception::catch(env, e))?;
+    let r = set_class_loader(env, &loader);
+    env.delete_local_ref(loader)?;
+
+    r
+}
+
+/// goes back to looking up classes with `FindClass`.
+pub fn clear_class_loader() {
+    GLOBAL_LOADER_ID.store(0, Ordering::Release);
+    *GLOBAL_LOADER.write().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+pub(crate) fn global_loader() -> Option<Arc<Loader>> {
+    GLOBAL_LOADER
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+pub(crate) fn global_loader_id() -> u64 {
+    GLOBAL_LOADER_ID.load(Ordering::Acquire)
+}
+
+/// the class loader of `obj` if it is a class, or of its class otherwise.
+fn class_loader_of<'local>(
+    env: &mut JNIEnv<'local>,
+    obj: &JObject,
+) -> Result<JObject<'local>, jni::errors::Error> {
+    let class_class = env.find_class("java/lang/Class")?;
+    let is_class = env.is_instance_of(obj, &class_class);
+    env.delete_local_ref(class_class)?;
+
+    let class = if is_class? {
+        env.new_local_ref(obj)?
+    } else {
+        env.get_object_class(obj)?.into()
+    };
+
+    let loader = env
+        .call_method(&class, "getClassLoader", "()Ljava/lang/ClassLoader;", &[])
+        .and_then(|v| v.l());
+    env.delete_local_ref(class)?;
+
+    loader
+}
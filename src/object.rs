@@ -0,0 +1,172 @@
+use core::fmt;
+
+use jni::objects::{JObject, JString, JValue};
+use jni::JNIEnv;
+
+use crate::vm::{with_attached_env, with_current_env};
+
+/// clears a pending exception left behind by a failed call.
+fn clear_exception(env: &mut JNIEnv) {
+    if env.exception_check().unwrap_or(false) {
+        let _ = env.exception_clear();
+    }
+}
+
+/// `IsSameObject`, falling back to comparing the references if no env is available.
+#[doc(hidden)]
+pub fn identity_eq(a: &JObject, b: &JObject) -> bool {
+    if a.as_raw() == b.as_raw() {
+        return true;
+    }
+
+    with_attached_env(|env| env.is_same_object(a, b).unwrap_or(false)).unwrap_or(false)
+}
+
+/// `System.identityHashCode`, or 0 if no env is available.
+#[doc(hidden)]
+pub fn identity_hash_code(obj: &JObject) -> i32 {
+    with_attached_env(|env| {
+        let r = env.call_static_method(
+            "java/lang/System",
+            "identityHashCode",
+            "(Ljava/lang/Object;)I",
+            &[JValue::Object(obj)],
+        );
+
+        match r.and_then(|v| v.i()) {
+            Ok(h) => h,
+            Err(_) => {
+                clear_exception(env);
+                0
+            }
+        }
+    })
+    .unwrap_or(0)
+}
+
+/// `a.equals(b)`, false if the call throws or no env is available.
+///
+/// the same reference (or two nulls) is always equal to itself, so `Eq` stays reflexive.
+#[doc(hidden)]
+pub fn java_equals(a: &JObject, b: &JObject) -> bool {
+    if a.as_raw() == b.as_raw() {
+        return true;
+    }
+
+    if a.is_null() {
+        return false;
+    }
+
+    with_attached_env(|env| {
+        let r = env.call_method(a, "equals", "(Ljava/lang/Object;)Z", &[JValue::Object(b)]);
+
+        match r.and_then(|v| v.z()) {
+            Ok(eq) => eq,
+            Err(_) => {
+                clear_exception(env);
+                false
+            }
+        }
+    })
+    .unwrap_or(false)
+}
+
+/// `obj.hashCode()`, 0 if the call throws or no env is available.
+#[doc(hidden)]
+pub fn java_hash_code(obj: &JObject) -> i32 {
+    with_attached_env(|env| {
+        let r = env.call_method(obj, "hashCode", "()I", &[]);
+
+        match r.and_then(|v| v.i()) {
+            Ok(h) => h,
+            Err(_) => {
+                clear_exception(env);
+                0
+            }
+        }
+    })
+    .unwrap_or(0)
+}
+
+/// calls a `()Ljava/lang/String;` method and converts the result.
+pub(crate) fn call_string_method(env: &mut JNIEnv, obj: &JObject, name: &str) -> Option<String> {
+    let r = env
+        .call_method(obj, name, "()Ljava/lang/String;", &[])
+        .and_then(|v| v.l());
+
+    let s = match r {
+        Ok(s) => JString::from(s),
+        Err(_) => {
+            clear_exception(env);
+            return None;
+        }
+    };
+
+    if s.is_null() {
+        return None;
+    }
+
+    // the method returns a String, `get_string` would also leak the locals of its type check
+    let string = unsafe { env.get_string_unchecked(&s) }.map(String::from).ok();
+    let _ = env.delete_local_ref(s);
+
+    string
+}
+
+/// `obj.getClass().getName()`
+pub(crate) fn class_name(env: &mut JNIEnv, obj: &JObject) -> Option<String> {
+    let class = env.get_object_class(obj).ok()?;
+    let name = call_string_method(env, &class, "getName");
+    let _ = env.delete_local_ref(class);
+
+    name
+}
+
+/// formats `obj.toString()`, attaching the current thread if needed.
+///
+/// falls back to `name@pointer` if no env is available or `toString` throws.
+#[doc(hidden)]
+pub fn fmt_display(name: &str, obj: &JObject, f: &mut fmt::Formatter) -> fmt::Result {
+    match with_attached_env(|env| call_string_method(env, obj, "toString")).flatten() {
+        Some(s) => f.write_str(&s),
+        None => write!(f, "{}@{:p}", name, obj.as_raw()),
+    }
+}
+
+/// formats the runtime class name and identity hash of `obj`.
+///
+/// only uses the env of the current thread if it is already attached,
+/// otherwise the raw reference is printed.
+#[doc(hidden)]
+pub fn fmt_debug(name: &str, obj: &JObject, f: &mut fmt::Formatter) -> fmt::Result {
+    let info = with_current_env(|env| {
+        let class = class_name(env, obj)?;
+
+        let hash = env
+            .call_static_method(
+                "java/lang/System",
+                "identityHashCode",
+                "(Ljava/lang/Object;)I",
+                &[JValue::Object(obj)],
+            )
+            .and_then(|v| v.i());
+
+        match hash {
+            Ok(hash) => Some((class, hash)),
+            Err(_) => {
+                clear_exception(env);
+                None
+            }
+        }
+    })
+    .flatten();
+
+    match info {
+        Some((class, hash)) => f
+            .debug_struct(name)
+            .field("class", &class)
+            .field("identity_hash", &format_args!("{:#x}", hash))
+            .finish(),
+        None => f.debug_struct(name).field("obj", &obj.as_raw()).finish(),
+    }
+}
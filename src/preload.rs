@@ -0,0 +1,140 @@
+use core::fmt;
+
+use jni::JNIEnv;
+
+use crate::reflect::fmt_found;
+use crate::{Error, JObjectType, JThrowableType, ObjectRef, Throwable};
+
+/// the kind of member a binding failed to resolve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemberKind {
+    Class,
+    Constructor,
+    Field,
+    StaticMethod,
+    Method,
+}
+
+impl fmt::Display for MemberKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MemberKind::Class => "class",
+            MemberKind::Constructor => "constructor",
+            MemberKind::Field => "field",
+            MemberKind::StaticMethod => "static method",
+            MemberKind::Method => "method",
+        })
+    }
+}
+
+/// a member of a binding that could not be resolved.
+#[derive(Debug)]
+pub struct BindingError {
+    pub kind: MemberKind,
+    pub name: &'static str,
+    /// the descriptor the binding expects.
+    pub descriptor: &'static str,
+    pub error: Error,
+}
+
+impl fmt::Display for BindingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}: ", self.kind, self.name, self.descriptor)?;
+
+        match &self.error {
+            Error::ClassNotFound { .. } => f.write_str("not found"),
+            Error::MemberNotFound { found, .. } => {
+                f.write_str("not found")?;
+                fmt_found(found, f)
+            }
+            e => fmt::Display::fmt(e, f),
+        }
+    }
+}
+
+/// every member of a binding that could not be resolved by `preload`.
+#[derive(Debug)]
+pub struct BindingErrors {
+    /// the binary name of the bound class.
+    pub class: &'static str,
+    pub errors: Vec<BindingError>,
+    /// the first exception left pending by `ExceptionPolicy::Propagate`, thrown again once all members are resolved.
+    pending: Option<Throwable>,
+}
+
+impl BindingErrors {
+    #[doc(hidden)]
+    pub fn new(class: &'static str) -> Self {
+        Self {
+            class,
+            errors: Vec::new(),
+            pending: None,
+        }
+    }
+
+    /// records `result` if it failed, clearing the pending exception until `into_result`.
+    ///
+    /// returns true if the member was resolved.
+    #[doc(hidden)]
+    pub fn check<T>(
+        &mut self,
+        env: &mut JNIEnv,
+        kind: MemberKind,
+        name: &'static str,
+        descriptor: &'static str,
+        result: Result<T, impl Into<Error>>,
+    ) -> bool {
+        let error = match result {
+            Ok(_) => return true,
+            Err(e) => e.into(),
+        };
+
+        if env.exception_check().unwrap_or(false) {
+            let throwable = env.exception_occurred();
+            let _ = env.exception_clear();
+
+            if let (None, Ok(throwable)) = (&self.pending, throwable) {
+                self.pending = ObjectRef::new::<Throwable>(env, &throwable)
+                    .ok()
+                    .map(|r| unsafe { Throwable::from_object_ref(r) });
+                let _ = env.delete_local_ref(throwable);
+            }
+        }
+
+        self.errors.push(BindingError {
+            kind,
+            name,
+            descriptor,
+            error,
+        });
+
+        false
+    }
+
+    #[doc(hidden)]
+    pub fn into_result(mut self, env: &mut JNIEnv) -> Result<(), Self> {
+        if let Some(pending) = self.pending.take() {
+            let _ = pending.throw(env);
+        }
+
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl fmt::Display for BindingErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to bind {}:", self.class)?;
+
+        for e in &self.errors {
+            write!(f, "\n    {}", e)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for BindingErrors {}
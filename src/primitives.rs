@@ -4,9 +4,11 @@ use jni::objects::JValue;
 use jni::JNIEnv;
 
 use crate::{
+    Error,
     IsA,
     JReturnType,
-    JBindingType
+    JBindingType,
+    ObjectRef
 };
 
 
@@ -34,8 +36,8 @@ unsafe impl JReturnType for jboolean {
     const JNI_RETURN_TY: jni::signature::ReturnType =
         jni::signature::ReturnType::Primitive(jni::signature::Primitive::Boolean);
 
-    unsafe fn from_jvalue(_env: &mut JNIEnv, value: jvalue) -> Self {
-        value.z
+    unsafe fn from_jvalue(_env: &mut JNIEnv, value: jvalue) -> Result<Self, Error> {
+        Ok(value.z)
     }
 }
 
@@ -64,8 +66,8 @@ unsafe impl JReturnType for jbyte {
     const JNI_RETURN_TY: jni::signature::ReturnType =
         jni::signature::ReturnType::Primitive(jni::signature::Primitive::Byte);
 
-    unsafe fn from_jvalue(_env: &mut JNIEnv, value: jvalue) -> Self {
-        value.b
+    unsafe fn from_jvalue(_env: &mut JNIEnv, value: jvalue) -> Result<Self, Error> {
+        Ok(value.b)
     }
 }
 
@@ -94,8 +96,8 @@ unsafe impl JReturnType for jchar {
     const JNI_RETURN_TY: jni::signature::ReturnType =
         jni::signature::ReturnType::Primitive(jni::signature::Primitive::Char);
 
-    unsafe fn from_jvalue(_env: &mut JNIEnv, value: jvalue) -> Self {
-        value.c
+    unsafe fn from_jvalue(_env: &mut JNIEnv, value: jvalue) -> Result<Self, Error> {
+        Ok(value.c)
     }
 }
 
@@ -124,8 +126,8 @@ unsafe impl JReturnType for jshort {
     const JNI_RETURN_TY: jni::signature::ReturnType =
         jni::signature::ReturnType::Primitive(jni::signature::Primitive::Short);
 
-    unsafe fn from_jvalue(_env: &mut JNIEnv, value: jvalue) -> Self {
-        value.s
+    unsafe fn from_jvalue(_env: &mut JNIEnv, value: jvalue) -> Result<Self, Error> {
+        Ok(value.s)
     }
 }
 
@@ -154,8 +156,8 @@ unsafe impl JReturnType for jint {
     const JNI_RETURN_TY: jni::signature::ReturnType =
         jni::signature::ReturnType::Primitive(jni::signature::Primitive::Int);
 
-    unsafe fn from_jvalue(_env: &mut JNIEnv, value: jvalue) -> Self {
-        value.i
+    unsafe fn from_jvalue(_env: &mut JNIEnv, value: jvalue) -> Result<Self, Error> {
+        Ok(value.i)
     }
 }
 
@@ -184,8 +186,8 @@ unsafe impl JReturnType for jlong {
     const JNI_RETURN_TY: jni::signature::ReturnType =
         jni::signature::ReturnType::Primitive(jni::signature::Primitive::Long);
 
-    unsafe fn from_jvalue(_env: &mut JNIEnv, value: jvalue) -> Self {
-        value.j
+    unsafe fn from_jvalue(_env: &mut JNIEnv, value: jvalue) -> Result<Self, Error> {
+        Ok(value.j)
     }
 }
 
@@ -214,8 +216,8 @@ unsafe impl JReturnType for jfloat {
     const JNI_RETURN_TY: jni::signature::ReturnType =
         jni::signature::ReturnType::Primitive(jni::signature::Primitive::Float);
 
-    unsafe fn from_jvalue(_env: &mut JNIEnv, value: jvalue) -> Self {
-        value.f
+    unsafe fn from_jvalue(_env: &mut JNIEnv, value: jvalue) -> Result<Self, Error> {
+        Ok(value.f)
     }
 }
 
@@ -244,13 +246,13 @@ unsafe impl JReturnType for jdouble {
     const JNI_RETURN_TY: jni::signature::ReturnType =
         jni::signature::ReturnType::Primitive(jni::signature::Primitive::Double);
 
-    unsafe fn from_jvalue(_env: &mut JNIEnv, value: jvalue) -> Self {
-        value.d
+    unsafe fn from_jvalue(_env: &mut JNIEnv, value: jvalue) -> Result<Self, Error> {
+        Ok(value.d)
     }
 }
 
 pub struct JByteArray {
-    _obj: jni::objects::GlobalRef,
+    _obj: ObjectRef,
 }
 
 unsafe impl JBindingType for JByteArray {
@@ -259,12 +261,12 @@ unsafe impl JBindingType for JByteArray {
 
     unsafe fn to_jvalue(&self) -> jvalue {
         jvalue {
-            l: self._obj.as_raw(),
+            l: self._obj.as_obj().as_raw(),
         }
     }
 
     unsafe fn to_jvalue_ref<'obj_ref>(&'obj_ref self) -> JValue<'_, 'obj_ref> {
-        JValue::Object(&self._obj)
+        JValue::Object(self._obj.as_obj())
     }
 }
 
@@ -273,12 +275,18 @@ unsafe impl JReturnType for JByteArray {
     const NAME: &'static str = <Self as JBindingType>::NAME;
     const JNI_RETURN_TY: jni::signature::ReturnType = jni::signature::ReturnType::Array;
 
-    unsafe fn from_jvalue(env: &mut JNIEnv, value: jvalue) -> Self {
-        let o = jni::objects::JByteArray::from_raw(value.l);
-        let r = env.new_global_ref(o).expect("failed to create global ref");
+    unsafe fn from_jvalue(env: &mut JNIEnv, value: jvalue) -> Result<Self, Error> {
+        Ok(JByteArray {
+            _obj: crate::reference::promote_local::<Self>(env, value.l)?,
+        })
+    }
 
-        JByteArray {
-            _obj: r,
-        }
+    unsafe fn type_mismatch(env: &mut JNIEnv, value: &jvalue) -> Result<Option<String>, Error> {
+        let class = env
+            .find_class(<Self as JBindingType>::SIGNATURE)?;
+        let r = crate::checked::type_mismatch(env, value.l, &class);
+        let _ = env.delete_local_ref(class);
+
+        r
     }
 }
\ No newline at end of file
@@ -0,0 +1,122 @@
+//! counts the references held by bound objects, per Rust type and call site.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::panic::Location;
+use std::sync::{Mutex, MutexGuard};
+
+type Key = (&'static str, &'static Location<'static>);
+
+static LIVE: Mutex<Option<HashMap<Key, usize>>> = Mutex::new(None);
+
+fn live() -> MutexGuard<'static, Option<HashMap<Key, usize>>> {
+    LIVE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// a live reference, removed from the counts on drop.
+pub(crate) struct Tracker {
+    key: Key,
+}
+
+impl Tracker {
+    #[track_caller]
+    pub(crate) fn new(type_name: &'static str) -> Self {
+        let key = (type_name, Location::caller());
+        *live().get_or_insert_with(HashMap::new).entry(key).or_insert(0) += 1;
+
+        Self { key }
+    }
+
+    /// the Rust type the reference is counted against.
+    pub(crate) fn type_name(&self) -> &'static str {
+        self.key.0
+    }
+}
+
+impl Drop for Tracker {
+    fn drop(&mut self) {
+        let mut live = live();
+        let Some(map) = live.as_mut() else { return };
+
+        if let Some(count) = map.get_mut(&self.key) {
+            *count -= 1;
+            if *count == 0 {
+                map.remove(&self.key);
+            }
+        }
+    }
+}
+
+/// live references created at one call site.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiveRefs {
+    /// the Rust type owning the references.
+    pub type_name: &'static str,
+    /// where the references were created.
+    pub location: &'static Location<'static>,
+    pub count: usize,
+}
+
+/// returns the live reference counts, sorted by count in descending order.
+pub fn live_refs() -> Vec<LiveRefs> {
+    let mut refs: Vec<LiveRefs> = live()
+        .iter()
+        .flatten()
+        .map(|(&(type_name, location), &count)| LiveRefs {
+            type_name,
+            location,
+            count,
+        })
+        .collect();
+
+    refs.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.type_name.cmp(b.type_name))
+            .then_with(|| a.location.cmp(b.location))
+    });
+
+    refs
+}
+
+/// total number of live references.
+pub fn live_ref_count() -> usize {
+    live().iter().flatten().map(|(_, count)| count).sum()
+}
+
+/// formats the live reference counts, one call site per line.
+pub fn dump_live_refs() -> String {
+    let mut out = String::new();
+
+    for r in live_refs() {
+        let _ = writeln!(out, "{:>8} {} at {}", r.count, r.type_name, r.location);
+    }
+
+    out
+}
+
+/// runs `f` and panics if it leaves behind more live references than it started with.
+///
+/// references created concurrently by other threads are counted as well.
+#[track_caller]
+pub fn assert_no_leaks<R>(f: impl FnOnce() -> R) -> R {
+    let before: HashMap<Key, usize> = live().clone().unwrap_or_default();
+
+    let r = f();
+
+    let mut leaks = String::new();
+
+    for l in live_refs() {
+        let prev = before.get(&(l.type_name, l.location)).copied().unwrap_or(0);
+
+        if l.count > prev {
+            let _ = writeln!(leaks, "{:>8} {} at {}", l.count - prev, l.type_name, l.location);
+        }
+    }
+
+    if !leaks.is_empty() {
+        panic!("references leaked:\n{}", leaks);
+    }
+
+    r
+}
@@ -0,0 +1,297 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::any::Any;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use jni::objects::JObject;
+use jni::sys::jobject;
+use jni::JNIEnv;
+
+use crate::Error;
+
+#[cfg(feature = "ref-tracking")]
+use crate::ref_tracking::Tracker;
+
+/// how global references are deleted when the last bound object holding them is dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropPolicy {
+    /// delete the reference immediately,
+    /// attaching the dropping thread to the vm for the call if it is not attached.
+    Immediate,
+    /// references dropped on threads that are not attached to the vm are queued.
+    ///
+    /// the queue is released by the next binding call on an attached thread,
+    /// by `release_pending_refs`, or by the dropping thread once `batch_size` references are queued.
+    Deferred { batch_size: usize },
+}
+
+/// 0 for `DropPolicy::Immediate`, the batch size otherwise.
+static DEFERRED_BATCH_SIZE: AtomicUsize = AtomicUsize::new(0);
+
+static PENDING: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+static PENDING_LEN: AtomicUsize = AtomicUsize::new(0);
+
+/// sets the policy used when dropping references from now on.
+pub fn set_drop_policy(policy: DropPolicy) {
+    let batch_size = match policy {
+        DropPolicy::Immediate => 0,
+        DropPolicy::Deferred { batch_size } => batch_size.max(1),
+    };
+
+    DEFERRED_BATCH_SIZE.store(batch_size, Ordering::Relaxed);
+}
+
+/// returns the current drop policy.
+pub fn drop_policy() -> DropPolicy {
+    match DEFERRED_BATCH_SIZE.load(Ordering::Relaxed) {
+        0 => DropPolicy::Immediate,
+        batch_size => DropPolicy::Deferred { batch_size },
+    }
+}
+
+/// number of references waiting to be deleted.
+pub fn pending_ref_count() -> usize {
+    PENDING_LEN.load(Ordering::Relaxed)
+}
+
+/// deletes every queued reference using `env`.
+pub fn release_pending_refs(env: &JNIEnv) {
+    if PENDING_LEN.load(Ordering::Relaxed) == 0 || crate::is_shut_down() {
+        return;
+    }
+
+    let pending = {
+        let mut queue = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+        PENDING_LEN.store(0, Ordering::Relaxed);
+        core::mem::take(&mut *queue)
+    };
+
+    for raw in pending {
+        unsafe { delete_global_ref(env, raw as jobject) }
+    }
+}
+
+/// forgets the queued references once the vm is gone.
+pub(crate) fn discard_pending_refs() {
+    let mut queue = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+    PENDING_LEN.store(0, Ordering::Relaxed);
+    queue.clear();
+}
+
+unsafe fn delete_global_ref(env: &JNIEnv, raw: jobject) {
+    let env = env.get_raw();
+    // safe to call with a pending exception
+    if let Some(delete) = (**env).DeleteGlobalRef {
+        delete(env, raw);
+    }
+}
+
+/// queues `raw`, returning the queue once it reaches `batch_size`.
+fn defer(raw: jobject, batch_size: usize) -> Option<Vec<usize>> {
+    let mut queue = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+    queue.push(raw as usize);
+
+    if queue.len() >= batch_size {
+        PENDING_LEN.store(0, Ordering::Relaxed);
+        return Some(core::mem::take(&mut *queue));
+    }
+
+    PENDING_LEN.store(queue.len(), Ordering::Relaxed);
+    None
+}
+
+fn release(raw: jobject) {
+    let Some(vm) = crate::java_vm() else { return };
+
+    if let Ok(env) = vm.get_env() {
+        unsafe { delete_global_ref(&env, raw) };
+        return;
+    }
+
+    let batch = match drop_policy() {
+        DropPolicy::Immediate => vec![raw as usize],
+        DropPolicy::Deferred { batch_size } => match defer(raw, batch_size) {
+            Some(batch) => batch,
+            None => return,
+        },
+    };
+
+    if let Ok(env) = vm.attach_current_thread() {
+        for raw in batch {
+            unsafe { delete_global_ref(&env, raw as jobject) }
+        }
+    }
+}
+
+/// a global reference deleted on drop, or leaked once the vm is shut down.
+pub(crate) struct Global {
+    obj: JObject<'static>,
+    /// a value computed once and shared by every clone of an `ObjectRef`.
+    extension: OnceLock<Box<dyn Any + Send + Sync>>,
+    /// counts the reference while it is alive, for the references of bound objects.
+    #[cfg(feature = "ref-tracking")]
+    tracker: Option<Tracker>,
+}
+
+impl Global {
+    /// creates a new global reference to `obj`, null if `obj` is null.
+    pub(crate) fn new(env: &JNIEnv, obj: &JObject) -> Result<Self, Error> {
+        crate::vm::ensure_running()?;
+
+        if obj.is_null() {
+            return Ok(Self {
+                obj: JObject::null(),
+                extension: OnceLock::new(),
+                #[cfg(feature = "ref-tracking")]
+                tracker: None,
+            });
+        }
+
+        let raw_env = env.get_raw();
+        let new = unsafe { (**raw_env).NewGlobalRef }
+            .ok_or(jni::errors::Error::JNIEnvMethodNotFound("NewGlobalRef"))?;
+
+        let raw = unsafe { new(raw_env, obj.as_raw()) };
+        if raw.is_null() {
+            return Err(jni::errors::Error::NullPtr("NewGlobalRef").into());
+        }
+
+        Ok(Self {
+            obj: unsafe { JObject::from_raw(raw) },
+            extension: OnceLock::new(),
+            #[cfg(feature = "ref-tracking")]
+            tracker: None,
+        })
+    }
+
+    /// counts the reference against `type_name` and the call site, unless it is null.
+    #[cfg(feature = "ref-tracking")]
+    #[track_caller]
+    fn track(mut self, type_name: &'static str) -> Self {
+        if !self.obj.is_null() {
+            self.tracker = Some(Tracker::new(type_name));
+        }
+
+        self
+    }
+
+    pub(crate) fn as_obj(&self) -> &JObject<'static> {
+        &self.obj
+    }
+
+    /// gives up ownership of the reference, which is never deleted.
+    pub(crate) fn leak(self) -> JObject<'static> {
+        let obj = unsafe { JObject::from_raw(self.obj.as_raw()) };
+        core::mem::forget(self);
+
+        obj
+    }
+}
+
+// a global reference may be used and deleted from any thread
+unsafe impl Send for Global {}
+unsafe impl Sync for Global {}
+
+impl Drop for Global {
+    fn drop(&mut self) {
+        let raw = core::mem::take(&mut self.obj).into_raw();
+
+        // the vm is gone along with its references
+        if !raw.is_null() && !crate::is_shut_down() {
+            release(raw);
+        }
+    }
+}
+
+/// the global reference held by a bound object.
+///
+/// clones share the same reference, which is deleted according to the `DropPolicy`
+/// when the last clone is dropped.
+///
+/// with the `ref-tracking` feature, every global reference is counted
+/// against its Rust type and the call site that created it, once for all its clones.
+pub struct ObjectRef {
+    inner: Arc<Global>,
+}
+
+impl ObjectRef {
+    /// creates a new global reference to `obj`, owned by a `T`.
+    #[doc(hidden)]
+    #[track_caller]
+    pub fn new<T: ?Sized>(env: &JNIEnv, obj: &JObject) -> Result<Self, Error> {
+        crate::vm::register_env(env);
+
+        let global = Global::new(env, obj)?;
+        #[cfg(feature = "ref-tracking")]
+        let global = global.track(core::any::type_name::<T>());
+
+        Ok(Self {
+            inner: Arc::new(global),
+        })
+    }
+
+    pub fn as_obj(&self) -> &JObject<'static> {
+        &self.inner.obj
+    }
+
+    /// creates an independent global reference to the same object,
+    /// deleted separately from this one.
+    #[track_caller]
+    pub fn new_ref(&self, env: &JNIEnv) -> Result<Self, Error> {
+        crate::vm::register_env(env);
+
+        let global = Global::new(env, self.as_obj())?;
+        #[cfg(feature = "ref-tracking")]
+        let global = match &self.inner.tracker {
+            Some(tracker) => global.track(tracker.type_name()),
+            None => global,
+        };
+
+        Ok(Self {
+            inner: Arc::new(global),
+        })
+    }
+
+    /// whether `self` and `other` share the same global reference.
+    pub fn shares_ref(&self, other: &ObjectRef) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    /// returns the value shared by the clones of this reference, computing it with `init` on first use.
+    ///
+    /// returns None if a value of another type was stored first.
+    pub(crate) fn extension<T: Any + Send + Sync>(&self, init: impl FnOnce() -> T) -> Option<&T> {
+        self.inner
+            .extension
+            .get_or_init(|| Box::new(init()))
+            .downcast_ref()
+    }
+}
+
+/// promotes the local reference `raw` returned from java to an `ObjectRef` owned by a `T`,
+/// deleting the local reference.
+///
+/// a failure to create the global reference is returned as `Error::Jni`,
+/// leaving a thrown `OutOfMemoryError` pending for the caller to capture with `ClassCache::catch`.
+///
+/// # Safety
+///
+/// `raw` must be a valid local reference or null.
+#[doc(hidden)]
+#[track_caller]
+pub unsafe fn promote_local<T: ?Sized>(env: &mut JNIEnv, raw: jobject) -> Result<ObjectRef, Error> {
+    crate::vm::register_env(env);
+
+    let obj = JObject::from_raw(raw);
+    let r = ObjectRef::new::<T>(env, &obj);
+    let _ = env.delete_local_ref(obj);
+
+    r
+}
+
+impl Clone for ObjectRef {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use core::fmt;
+
+use jni::objects::{JClass, JObject, JObjectArray};
+use jni::JNIEnv;
+
+use crate::object::call_string_method;
+use crate::MemberKind;
+
+/// `java.lang.reflect.Modifier.STATIC`
+const STATIC: i32 = 0x0008;
+
+/// a member found by reflection with the name of a member that could not be resolved.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FoundMember {
+    /// the binary name of the class declaring the member.
+    pub class: String,
+    pub is_static: bool,
+    pub descriptor: String,
+}
+
+impl fmt::Display for FoundMember {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_static {
+            f.write_str("static ")?;
+        }
+
+        write!(f, "{} in {}", self.descriptor, self.class)
+    }
+}
+
+/// writes `, found: a, b` if any member was found.
+pub(crate) fn fmt_found(found: &[FoundMember], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (i, member) in found.iter().enumerate() {
+        f.write_str(if i == 0 { ", found: " } else { ", " })?;
+        fmt::Display::fmt(member, f)?;
+    }
+
+    Ok(())
+}
+
+/// the members of `class` and its supertypes named `name`, the constructors of `class` for `MemberKind::Constructor`.
+///
+/// any error stops the search, returning the members found so far.
+pub(crate) fn find_members(env: &mut JNIEnv, class: &JClass, kind: MemberKind, name: &str) -> Vec<FoundMember> {
+    let mut found = Vec::new();
+
+    let (getter, array_ty) = match kind {
+        MemberKind::Class => return found,
+        MemberKind::Constructor => ("getDeclaredConstructors", "()[Ljava/lang/reflect/Constructor;"),
+        MemberKind::Field => ("getDeclaredFields", "()[Ljava/lang/reflect/Field;"),
+        MemberKind::StaticMethod | MemberKind::Method => ("getDeclaredMethods", "()[Ljava/lang/reflect/Method;"),
+    };
+
+    let r = env.with_local_frame(32, |env| -> Result<(), jni::errors::Error> {
+        // JNI also resolves members inherited from superclasses and interfaces
+        let mut pending = vec![env.new_local_ref(class)?];
+        let mut visited = Vec::new();
+
+        while let Some(class) = pending.pop() {
+            let class_name = call_string_method(env, &class, "getName").unwrap_or_default();
+            if visited.contains(&class_name) {
+                env.delete_local_ref(class)?;
+                continue;
+            }
+
+            let members = JObjectArray::from(env.call_method(&class, getter, array_ty, &[])?.l()?);
+
+            for i in 0..env.get_array_length(&members)? {
+                let member = env.get_object_array_element(&members, i)?;
+
+                if kind == MemberKind::Constructor
+                    || call_string_method(env, &member, "getName").as_deref() == Some(name)
+                {
+                    found.push(describe(env, &member, kind, &class_name)?);
+                }
+                env.delete_local_ref(member)?;
+            }
+            env.delete_local_ref(members)?;
+
+            visited.push(class_name);
+
+            if kind == MemberKind::Constructor {
+                break;
+            }
+
+            let superclass = env.call_method(&class, "getSuperclass", "()Ljava/lang/Class;", &[])?.l()?;
+            if !superclass.is_null() {
+                pending.push(superclass);
+            }
+
+            let interfaces = JObjectArray::from(
+                env.call_method(&class, "getInterfaces", "()[Ljava/lang/Class;", &[])?.l()?,
+            );
+            for i in 0..env.get_array_length(&interfaces)? {
+                pending.push(env.get_object_array_element(&interfaces, i)?);
+            }
+            env.delete_local_ref(interfaces)?;
+            env.delete_local_ref(class)?;
+        }
+
+        Ok(())
+    });
+
+    if r.is_err() && env.exception_check().unwrap_or(false) {
+        let _ = env.exception_clear();
+    }
+
+    found
+}
+
+/// the descriptor and modifiers of a `Constructor`, `Field` or `Method`.
+fn describe(env: &mut JNIEnv, member: &JObject, kind: MemberKind, class: &str) -> Result<FoundMember, jni::errors::Error> {
+    env.with_local_frame(16, |env| {
+        let modifiers = env.call_method(member, "getModifiers", "()I", &[])?.i()?;
+
+        let descriptor = if kind == MemberKind::Field {
+            let ty = env.call_method(member, "getType", "()Ljava/lang/Class;", &[])?.l()?;
+            type_descriptor(env, &ty)
+        } else {
+            let params = JObjectArray::from(
+                env.call_method(member, "getParameterTypes", "()[Ljava/lang/Class;", &[])?.l()?,
+            );
+            let mut descriptor = String::from("(");
+
+            for i in 0..env.get_array_length(&params)? {
+                let param = env.get_object_array_element(&params, i)?;
+                descriptor.push_str(&type_descriptor(env, &param));
+                env.delete_local_ref(param)?;
+            }
+            descriptor.push(')');
+
+            if kind == MemberKind::Constructor {
+                descriptor.push('V');
+            } else {
+                let ret = env.call_method(member, "getReturnType", "()Ljava/lang/Class;", &[])?.l()?;
+                descriptor.push_str(&type_descriptor(env, &ret));
+            }
+
+            descriptor
+        };
+
+        Ok(FoundMember {
+            class: class.replace('.', "/"),
+            is_static: modifiers & STATIC != 0,
+            descriptor,
+        })
+    })
+}
+
+/// the descriptor of the type represented by the `Class` object `class`, e.g. `I` or `Ljava/lang/String;`.
+fn type_descriptor(env: &mut JNIEnv, class: &JObject) -> String {
+    let name = call_string_method(env, class, "getName").unwrap_or_default();
+
+    match name.as_str() {
+        "boolean" => "Z".into(),
+        "byte" => "B".into(),
+        "char" => "C".into(),
+        "short" => "S".into(),
+        "int" => "I".into(),
+        "long" => "J".into(),
+        "float" => "F".into(),
+        "double" => "D".into(),
+        "void" => "V".into(),
+        // arrays are named by their descriptor already, e.g. `[Ljava.lang.String;`
+        _ if name.starts_with('[') => name.replace('.', "/"),
+        _ => format!("L{};", name.replace('.', "/")),
+    }
+}
@@ -0,0 +1,62 @@
+use jni::JNIEnv;
+
+use crate::exception::suspend_exception;
+use crate::BindingErrors;
+
+/// a binding created by `import_class!` or `import_interface!`.
+#[derive(Debug)]
+pub struct RegisteredClass {
+    /// the path of the generated Rust type.
+    pub rust_name: &'static str,
+    /// the binary name of the java class, e.g. `java/lang/String`.
+    pub name: &'static str,
+    /// the type descriptor of the java class, e.g. `Ljava/lang/String;`.
+    pub descriptor: &'static str,
+    preload: fn(&mut JNIEnv) -> Result<(), BindingErrors>,
+}
+
+impl RegisteredClass {
+    #[doc(hidden)]
+    pub const fn new(
+        rust_name: &'static str,
+        name: &'static str,
+        descriptor: &'static str,
+        preload: fn(&mut JNIEnv) -> Result<(), BindingErrors>,
+    ) -> Self {
+        Self {
+            rust_name,
+            name,
+            descriptor,
+            preload,
+        }
+    }
+
+    /// resolves the class and every member of the binding, see `JObjectType::preload`.
+    pub fn preload(&self, env: &mut JNIEnv) -> Result<(), BindingErrors> {
+        (self.preload)(env)
+    }
+}
+
+inventory::collect!(RegisteredClass);
+
+/// every binding in the program.
+pub fn registered_classes() -> impl Iterator<Item = &'static RegisteredClass> {
+    inventory::iter::<RegisteredClass>.into_iter()
+}
+
+/// preloads every binding in the program, reporting the bindings that failed.
+///
+/// call this where the right class loader is available, e.g. in `JNI_OnLoad`,
+/// so that every class and member ID is cached before other threads use them.
+pub fn preload_all(env: &mut JNIEnv) -> Result<(), Vec<BindingErrors>> {
+    let errors: Vec<BindingErrors> = registered_classes()
+        // an exception left pending by `ExceptionPolicy::Propagate` is kept until every binding is preloaded
+        .filter_map(|class| suspend_exception(env, |env| class.preload(env).err()))
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
@@ -0,0 +1,184 @@
+use core::ops::Deref;
+use std::backtrace::Backtrace;
+
+use jni::objects::{JThrowable, JValue};
+use jni::JNIEnv;
+
+use crate::backtrace::push_rust_frames;
+use crate::exception::{catch, suspend_exception};
+use crate::reflect::find_members;
+use crate::{Error, JBindingType, JObjectType, MemberKind, ObjectRef, RuntimeException, Throwable};
+
+/// a bound subclass of `java.lang.Throwable`.
+///
+/// implemented for `Throwable` and every class that `extends` a throwable binding, e.g. `RuntimeException`.
+pub trait JThrowableType: JObjectType {
+    /// creates an instance of this exception class with `message` and throws it,
+    /// leaving it pending for java code to catch.
+    ///
+    /// the class must have a `(String)` constructor.
+    #[track_caller]
+    fn throw_new(env: &mut JNIEnv, message: &str) -> Result<(), Error> {
+        let exception = new_exception::<Self>(env, message, None)?;
+
+        throw(env, &exception)
+    }
+
+    /// throws this object, leaving it pending for java code to catch.
+    fn throw(&self, env: &mut JNIEnv) -> Result<(), Error> {
+        throw(env, self)
+    }
+}
+
+impl JThrowableType for Throwable {}
+
+impl<T> JThrowableType for T
+where
+    T: JObjectType + Deref,
+    T::Target: JThrowableType,
+{
+}
+
+/// maps a rust error to a java exception, e.g. to throw it from a native method or callback.
+///
+/// ```ignore
+/// impl IntoJavaException for ParseError {
+///     type Exception = IllegalArgumentException;
+///
+///     fn message(&self) -> String {
+///         self.to_string()
+///     }
+/// }
+///
+/// if let Err(e) = parse(input) {
+///     e.throw(env)?;
+/// }
+/// ```
+pub trait IntoJavaException {
+    /// the class of the exception, with a `(String)` constructor.
+    type Exception: JThrowableType;
+
+    /// the message of the exception.
+    fn message(&self) -> String;
+
+    /// the cause of the exception.
+    fn cause(&self, _env: &mut JNIEnv) -> Option<Throwable> {
+        None
+    }
+
+    /// creates the exception, without throwing it.
+    fn to_java_exception(&self, env: &mut JNIEnv) -> Result<Throwable, Error> {
+        let cause = self.cause(env);
+
+        new_exception::<Self::Exception>(env, &self.message(), cause.as_ref())
+    }
+
+    /// throws the exception, leaving it pending for java code to catch.
+    fn throw(&self, env: &mut JNIEnv) -> Result<(), Error> {
+        let exception = self.to_java_exception(env)?;
+
+        throw(env, &exception)
+    }
+}
+
+/// a java exception is thrown again as is, any other error as a `RuntimeException`.
+impl IntoJavaException for Error {
+    type Exception = RuntimeException;
+
+    fn message(&self) -> String {
+        self.to_string()
+    }
+
+    fn to_java_exception(&self, env: &mut JNIEnv) -> Result<Throwable, Error> {
+        match self {
+            Error::JavaException(e) => Ok(e.throwable.clone()),
+            _ => new_exception::<RuntimeException>(env, &self.message(), None),
+        }
+    }
+}
+
+/// creates an instance of the exception class `T` with `message` and an optional `cause`,
+/// with the frames of the current rust backtrace on top of its stack trace.
+#[track_caller]
+pub(crate) fn new_exception<T: JObjectType>(
+    env: &mut JNIEnv,
+    message: &str,
+    cause: Option<&Throwable>,
+) -> Result<Throwable, Error> {
+    const DESCRIPTOR: &str = "(Ljava/lang/String;)V";
+
+    let cache = T::class_cache();
+    let class = T::class(env)?;
+    let throwable = Throwable::class(env)?;
+
+    if !env
+        .is_assignable_from(class, throwable)
+        .map_err(|e| cache.report(catch(env, e), "<init>"))?
+    {
+        return Err(Error::NotThrowable { class: T::NAME });
+    }
+
+    let constructor = env.get_method_id(class, "<init>", DESCRIPTOR).map_err(|e| {
+        match cache.report(catch(env, e), "<init>") {
+            Error::JavaException(e) if e.is_instance_of(env, "java/lang/NoSuchMethodError") => {
+                Error::MemberNotFound {
+                    class: T::NAME,
+                    kind: MemberKind::Constructor,
+                    name: "<init>",
+                    descriptor: DESCRIPTOR,
+                    found: suspend_exception(env, |env| find_members(env, class, MemberKind::Constructor, "<init>")),
+                }
+            }
+            e => e,
+        }
+    })?;
+
+    let message = env.new_string(message).map_err(|e| cache.report(catch(env, e), "<init>"))?;
+    let obj = unsafe { env.new_object_unchecked(class, constructor, &[JValue::Object(&message).as_jni()]) };
+    let _ = env.delete_local_ref(message);
+    let obj = obj.map_err(|e| cache.report(catch(env, e), "<init>"))?;
+
+    let r = ObjectRef::new::<Throwable>(env, &obj);
+    let _ = env.delete_local_ref(obj);
+    let r = r.map_err(|e| cache.catch(env, e, "<init>"))?;
+    let exception = unsafe { Throwable::from_object_ref(r) };
+
+    push_rust_frames(env, exception.object_ref().as_obj(), &Backtrace::capture())
+        .map_err(|e| cache.report(e, "<init>"))?;
+
+    if let Some(cause) = cause {
+        env.call_method(
+            exception.object_ref().as_obj(),
+            "initCause",
+            "(Ljava/lang/Throwable;)Ljava/lang/Throwable;",
+            &[JValue::Object(cause.object_ref().as_obj())],
+        )
+        .and_then(|v| v.l())
+        .and_then(|o| env.delete_local_ref(o))
+        .map_err(|e| cache.report(catch(env, e), "initCause"))?;
+    }
+
+    Ok(exception)
+}
+
+/// throws `obj`, which must be an instance of `java.lang.Throwable`.
+fn throw<T: JObjectType>(env: &mut JNIEnv, obj: &T) -> Result<(), Error> {
+    crate::vm::ensure_running()?;
+
+    let throwable = Throwable::class(env)?;
+    let obj = obj.object_ref().as_obj();
+
+    if obj.is_null()
+        || !env
+            .is_instance_of(obj, throwable)
+            .map_err(|e| T::class_cache().report(catch(env, e), "throw"))?
+    {
+        return Err(Error::NotThrowable {
+            class: <T as JBindingType>::NAME,
+        });
+    }
+
+    let obj = unsafe { JThrowable::from_raw(obj.as_raw()) };
+
+    Ok(env.throw(&obj)?)
+}
@@ -0,0 +1,191 @@
+use core::fmt;
+
+use jni::objects::{JObject, JObjectArray};
+use jni::JNIEnv;
+
+use crate::exception::catch;
+use crate::object::call_string_method;
+use crate::vm::with_attached_env;
+use crate::{import_class, Error, JObjectType, ObjectRef};
+
+import_class! {
+    "java/lang/Throwable";
+    Throwable;
+    equality identity;
+    fn getCause(&self) -> Throwable;
+}
+
+import_class! {
+    "java/lang/RuntimeException";
+    RuntimeException;
+    extends Throwable;
+    equality identity;
+}
+
+/// an element of the stack trace of a `Throwable`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StackTraceElement {
+    /// the binary name of the class, e.g. `java.lang.String`.
+    pub class_name: String,
+    pub method_name: String,
+    pub file_name: Option<String>,
+    /// None if the line is unknown.
+    pub line_number: Option<u32>,
+    pub native: bool,
+}
+
+impl StackTraceElement {
+    fn new(env: &mut JNIEnv, element: &JObject) -> Result<Self, Error> {
+        let line = env
+            .call_method(element, "getLineNumber", "()I", &[])
+            .and_then(|v| v.i())
+            .map_err(|e| catch(env, e))?;
+        let native = env
+            .call_method(element, "isNativeMethod", "()Z", &[])
+            .and_then(|v| v.z())
+            .map_err(|e| catch(env, e))?;
+
+        Ok(Self {
+            class_name: call_string_method(env, element, "getClassName").unwrap_or_default(),
+            method_name: call_string_method(env, element, "getMethodName").unwrap_or_default(),
+            file_name: call_string_method(env, element, "getFileName"),
+            line_number: u32::try_from(line).ok(),
+            native,
+        })
+    }
+}
+
+impl fmt::Display for StackTraceElement {
+    /// formats the element like java does, e.g. `java.lang.String.charAt(String.java:1515)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.class_name.is_empty() {
+            write!(f, "{}.", self.class_name)?;
+        }
+        write!(f, "{}(", self.method_name)?;
+
+        match (&self.file_name, self.line_number) {
+            _ if self.native => f.write_str("Native Method")?,
+            (Some(file), Some(line)) => write!(f, "{}:{}", file, line)?,
+            (Some(file), None) => f.write_str(file)?,
+            (None, _) => f.write_str("Unknown Source")?,
+        }
+
+        f.write_str(")")
+    }
+}
+
+/// the elements of a `Throwable[]`, skipping null ones.
+fn throwables(env: &mut JNIEnv, array: JObject) -> Result<Vec<Throwable>, Error> {
+    if array.is_null() {
+        return Ok(Vec::new());
+    }
+
+    let array = JObjectArray::from(array);
+    let len = env.get_array_length(&array)?;
+    let mut throwables = Vec::with_capacity(len as usize);
+
+    for i in 0..len {
+        let element = env.get_object_array_element(&array, i)?;
+
+        if !element.is_null() {
+            let r = ObjectRef::new::<Throwable>(env, &element)?;
+            throwables.push(unsafe { Throwable::from_object_ref(r) });
+        }
+        env.delete_local_ref(element)?;
+    }
+
+    env.delete_local_ref(array)?;
+
+    Ok(throwables)
+}
+
+/// the elements of `throwable.getStackTrace()`.
+pub(crate) fn stack_trace(env: &mut JNIEnv, throwable: &JObject) -> Result<Vec<StackTraceElement>, Error> {
+    let elements = env
+        .call_method(
+            throwable,
+            "getStackTrace",
+            "()[Ljava/lang/StackTraceElement;",
+            &[],
+        )
+        .and_then(|v| v.l())
+        .map_err(|e| catch(env, e))?;
+
+    if elements.is_null() {
+        return Ok(Vec::new());
+    }
+
+    let elements = JObjectArray::from(elements);
+    let len = env.get_array_length(&elements)?;
+    let mut trace = Vec::with_capacity(len as usize);
+
+    for i in 0..len {
+        let element = env.get_object_array_element(&elements, i)?;
+        let r = StackTraceElement::new(env, &element);
+        env.delete_local_ref(element)?;
+
+        trace.push(r?);
+    }
+
+    env.delete_local_ref(elements)?;
+
+    Ok(trace)
+}
+
+impl Throwable {
+    /// `getMessage()`
+    pub fn message(&self, env: &mut JNIEnv) -> Result<Option<String>, Error> {
+        crate::vm::ensure_running()?;
+
+        Ok(call_string_method(env, self._obj.as_obj(), "getMessage"))
+    }
+
+    /// `getCause()`, None if the cause is unknown.
+    #[track_caller]
+    pub fn cause(&self, env: &mut JNIEnv) -> Result<Option<Throwable>, Error> {
+        let cause = self.get_cause(env)?;
+
+        if cause._obj.as_obj().is_null() {
+            return Ok(None);
+        }
+
+        Ok(Some(cause))
+    }
+
+    /// `getSuppressed()`
+    pub fn suppressed(&self, env: &mut JNIEnv) -> Result<Vec<Throwable>, Error> {
+        crate::vm::ensure_running()?;
+
+        let array = env
+            .call_method(
+                self._obj.as_obj(),
+                "getSuppressed",
+                "()[Ljava/lang/Throwable;",
+                &[],
+            )
+            .and_then(|v| v.l())
+            .map_err(|e| Self::__class_cache().report(catch(env, e), "getSuppressed"))?;
+
+        throwables(env, array)
+    }
+
+    /// `getStackTrace()`
+    pub fn stack_trace(&self, env: &mut JNIEnv) -> Result<Vec<StackTraceElement>, Error> {
+        crate::vm::ensure_running()?;
+
+        stack_trace(env, self._obj.as_obj()).map_err(|e| Self::__class_cache().report(e, "getStackTrace"))
+    }
+}
+
+impl std::error::Error for Throwable {
+    /// `getCause()`, attaching the current thread if needed.
+    ///
+    /// the cause is looked up once and shared by the clones of this object.
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        let cause = self._obj.extension(|| {
+            with_attached_env(|env| self.cause(env).ok().flatten()).flatten()
+        })?;
+
+        cause.as_ref().map(|c| c as _)
+    }
+}
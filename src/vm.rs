@@ -0,0 +1,102 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use jni::{JNIEnv, JavaVM};
+
+use crate::exception::suspend_exception;
+use crate::Error;
+
+static JAVA_VM: OnceLock<JavaVM> = OnceLock::new();
+static SHUT_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// set the `JavaVM` used by bindings whenever they need an env
+/// without one being passed in (e.g. `PartialEq`, `Hash`).
+///
+/// the vm is otherwise captured from the first env handed to a binding.
+/// returns false if a vm was already set.
+pub fn set_java_vm(vm: JavaVM) -> bool {
+    JAVA_VM.set(vm).is_ok()
+}
+
+/// returns the `JavaVM` used by bindings, if one is known and `shutdown` was not called.
+pub fn java_vm() -> Option<&'static JavaVM> {
+    if is_shut_down() {
+        return None;
+    }
+
+    JAVA_VM.get()
+}
+
+/// marks the vm as destroyed, call this before destroying the vm.
+///
+/// from then on dropping bound objects no longer deletes their references,
+/// the cached classes and member IDs are never used again,
+/// and calls through bindings return `Error::VmDestroyed`.
+pub fn shutdown() {
+    SHUT_DOWN.store(true, Ordering::Release);
+    crate::reference::discard_pending_refs();
+}
+
+/// whether `shutdown` was called.
+pub fn is_shut_down() -> bool {
+    SHUT_DOWN.load(Ordering::Acquire)
+}
+
+/// calls `shutdown`, then destroys the vm.
+///
+/// # Safety
+///
+/// see `jni::JavaVM::destroy`.
+pub unsafe fn destroy_java_vm() -> Result<(), Error> {
+    shutdown();
+
+    match JAVA_VM.get() {
+        Some(vm) => Ok(vm.destroy()?),
+        None => Ok(()),
+    }
+}
+
+/// returns `Error::VmDestroyed` once `shutdown` was called.
+pub(crate) fn ensure_running() -> Result<(), Error> {
+    if is_shut_down() {
+        return Err(Error::VmDestroyed);
+    }
+
+    Ok(())
+}
+
+/// called whenever an env is handed to a binding.
+///
+/// captures the `JavaVM` of `env` if none is known yet,
+/// and releases the references queued by `DropPolicy::Deferred`.
+#[doc(hidden)]
+pub fn register_env(env: &JNIEnv) {
+    if is_shut_down() {
+        return;
+    }
+
+    if JAVA_VM.get().is_none() {
+        if let Ok(vm) = env.get_java_vm() {
+            let _ = JAVA_VM.set(vm);
+        }
+    }
+
+    crate::reference::release_pending_refs(env);
+}
+
+/// runs `f` with the env of the current thread,
+/// attaching the thread to the vm for the duration of the call if needed.
+///
+/// a pending exception is set aside while `f` runs.
+pub(crate) fn with_attached_env<R>(f: impl FnOnce(&mut JNIEnv) -> R) -> Option<R> {
+    let vm = java_vm()?;
+    let mut guard = vm.attach_current_thread().ok()?;
+    Some(suspend_exception(&mut guard, f))
+}
+
+/// runs `f` with the env of the current thread, only if it is already attached.
+pub(crate) fn with_current_env<R>(f: impl FnOnce(&mut JNIEnv) -> R) -> Option<R> {
+    let vm = java_vm()?;
+    let mut env = vm.get_env().ok()?;
+    Some(suspend_exception(&mut env, f))
+}
@@ -0,0 +1,49 @@
+//! runs against a jvm started by the test, requires a JDK to be installed.
+mod common;
+
+use common::vm;
+use jni_bind::{import_class, jint, with_local_frame_returning, Error, JObjectType};
+
+import_class! {
+    "java/lang/Integer";
+    Integer;
+    static fn valueOf(i: jint) -> Integer;
+    fn compareTo(&self, o: Integer) -> jint;
+}
+
+import_class! {
+    "java/lang/String";
+    JavaString;
+}
+
+#[test]
+fn objects_wrapped_as_the_wrong_binding_are_rejected() {
+    let mut env = vm().attach_current_thread().unwrap();
+
+    let integer = Integer::value_of(&mut env, 7).unwrap();
+    let string: JavaString = with_local_frame_returning(&mut env, 1, |env| {
+        Ok::<_, Error>(env.new_string("7")?.into())
+    })
+    .unwrap();
+    let fake = unsafe { Integer::from_object_ref(string.object_ref().clone()) };
+
+    match integer.compare_to(&mut env, &fake) {
+        Err(Error::WrongType {
+            class,
+            member,
+            value,
+            expected,
+            found,
+        }) => {
+            assert_eq!(class, "java/lang/Integer");
+            assert_eq!(member, "compareTo");
+            assert_eq!(value, "o");
+            assert_eq!(expected, "java/lang/Integer");
+            assert_eq!(found, "java/lang/String");
+        }
+        r => panic!("expected a wrong type, got {:?}", r),
+    }
+    assert!(!env.exception_check().unwrap());
+
+    assert_eq!(integer.compare_to(&mut env, &integer).unwrap(), 0);
+}
@@ -0,0 +1,49 @@
+//! runs against a jvm started by the test, requires a JDK to be installed.
+mod common;
+
+use std::sync::Barrier;
+use std::thread;
+
+use common::vm;
+use jni_bind::{import_class, jint, JObjectType};
+
+const THREADS: usize = 16;
+
+import_class! {
+    "java/lang/Integer";
+    Integer;
+    static fn valueOf(i: jint) -> Integer;
+    fn intValue(&self) -> jint;
+}
+
+#[test]
+fn cold_class_cache_is_shared_between_threads() {
+    let vm = vm();
+    let barrier = &Barrier::new(THREADS);
+
+    let classes: Vec<usize> = thread::scope(|s| {
+        let handles: Vec<_> = (0..THREADS as jint)
+            .map(|i| {
+                s.spawn(move || {
+                    let mut env = vm.attach_current_thread().unwrap();
+
+                    // every thread misses the cache at the same time
+                    barrier.wait();
+                    let value = Integer::value_of(&mut env, i).unwrap();
+                    assert_eq!(value.int_value(&mut env).unwrap(), i);
+
+                    <Integer as JObjectType>::class(&mut env).unwrap() as *const _ as usize
+                })
+            })
+            .collect();
+
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    assert!(classes.windows(2).all(|w| w[0] == w[1]));
+
+    // the class handed out first is still valid
+    let mut env = vm.attach_current_thread().unwrap();
+    let value = Integer::value_of(&mut env, 42).unwrap();
+    assert_eq!(value.int_value(&mut env).unwrap(), 42);
+}
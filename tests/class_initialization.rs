@@ -0,0 +1,68 @@
+//! runs against a jvm started by the test, requires a JDK to be installed.
+mod common;
+
+use common::vm;
+use jni::objects::{JObject, JValue};
+use jni::JNIEnv;
+use jni_bind::{import_class, Error, JObjectType};
+
+// `java.sql` classes are loaded by the platform class loader, the bootstrap loader cannot see them
+import_class! {
+    "java/sql/Date";
+    HiddenDate;
+    initialize explicit;
+}
+
+import_class! {
+    "java/sql/Time";
+    VisibleTime;
+    initialize explicit;
+}
+
+fn set_context_class_loader(env: &mut JNIEnv, loader: &JObject) {
+    let thread = env
+        .call_static_method(
+            "java/lang/Thread",
+            "currentThread",
+            "()Ljava/lang/Thread;",
+            &[],
+        )
+        .unwrap()
+        .l()
+        .unwrap();
+    env.call_method(
+        &thread,
+        "setContextClassLoader",
+        "(Ljava/lang/ClassLoader;)V",
+        &[JValue::Object(loader)],
+    )
+    .unwrap();
+}
+
+#[test]
+fn explicit_initialization_looks_up_classes_with_the_context_class_loader() {
+    let mut env = vm().attach_current_thread().unwrap();
+
+    set_context_class_loader(&mut env, &JObject::null());
+    match HiddenDate::class(&mut env) {
+        Err(Error::ClassNotFound { class }) => assert_eq!(class, "java/sql/Date"),
+        r => panic!(
+            "expected ClassNotFound, got {:?}",
+            r.map(|_| ()).map_err(|e| e.to_string())
+        ),
+    }
+
+    let platform = env
+        .call_static_method(
+            "java/lang/ClassLoader",
+            "getPlatformClassLoader",
+            "()Ljava/lang/ClassLoader;",
+            &[],
+        )
+        .unwrap()
+        .l()
+        .unwrap();
+    set_context_class_loader(&mut env, &platform);
+    VisibleTime::class(&mut env).unwrap();
+    VisibleTime::initialize(&mut env).unwrap();
+}
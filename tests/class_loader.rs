@@ -0,0 +1,71 @@
+//! runs against a jvm started by the test, requires a JDK to be installed.
+mod common;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+use common::vm;
+use jni::objects::JObject;
+use jni::JNIEnv;
+use jni_bind::{import_class, jint, JObjectType};
+
+const THREADS: usize = 8;
+const SWITCHES: usize = 2_000;
+
+import_class! {
+    "java/lang/Integer";
+    Integer;
+    static fn valueOf(i: jint) -> Integer;
+    fn intValue(&self) -> jint;
+}
+
+fn loader<'local>(env: &mut JNIEnv<'local>, getter: &str) -> JObject<'local> {
+    env.call_static_method("java/lang/ClassLoader", getter, "()Ljava/lang/ClassLoader;", &[])
+        .and_then(|v| v.l())
+        .unwrap()
+}
+
+#[test]
+fn switching_class_loaders_keeps_classes_in_use_valid() {
+    let vm = vm();
+    let mut env = vm.attach_current_thread().unwrap();
+    let system = loader(&mut env, "getSystemClassLoader");
+    let platform = loader(&mut env, "getPlatformClassLoader");
+    let done = &AtomicBool::new(false);
+
+    thread::scope(|s| {
+        for i in 0..THREADS as jint {
+            s.spawn(move || {
+                let mut env = vm.attach_current_thread().unwrap();
+
+                while !done.load(Ordering::Relaxed) {
+                    env.with_local_frame(16, |env| -> jni::errors::Result<()> {
+                        let value = Integer::value_of(env, i).unwrap();
+                        assert_eq!(value.int_value(env).unwrap(), i);
+                        Ok(())
+                    })
+                    .unwrap();
+                }
+            });
+        }
+
+        for i in 0..SWITCHES {
+            match i % 3 {
+                0 => jni_bind::set_class_loader(&env, &system).unwrap(),
+                1 => jni_bind::set_class_loader(&env, &platform).unwrap(),
+                _ => jni_bind::clear_class_loader(),
+            }
+        }
+        done.store(true, Ordering::Relaxed);
+    });
+
+    // setting a loader again reuses the class cached for it
+    jni_bind::set_class_loader(&env, &system).unwrap();
+    let first = <Integer as JObjectType>::class(&mut env).unwrap() as *const _;
+    jni_bind::set_class_loader(&env, &platform).unwrap();
+    jni_bind::set_class_loader(&env, &system).unwrap();
+    let again = <Integer as JObjectType>::class(&mut env).unwrap() as *const _;
+    assert_eq!(first, again);
+
+    jni_bind::clear_class_loader();
+}
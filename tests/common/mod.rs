@@ -0,0 +1,13 @@
+use std::sync::OnceLock;
+
+use jni::{InitArgsBuilder, JavaVM};
+
+/// the vm shared by the tests of a test binary.
+pub fn vm() -> &'static JavaVM {
+    static VM: OnceLock<JavaVM> = OnceLock::new();
+
+    VM.get_or_init(|| {
+        let args = InitArgsBuilder::new().option("-Xcheck:jni").build().unwrap();
+        JavaVM::new(args).unwrap()
+    })
+}
@@ -0,0 +1,54 @@
+//! runs against a jvm started by the test, requires a JDK to be installed.
+mod common;
+
+use std::thread;
+
+use common::vm;
+use jni_bind::{
+    import_class, jint, pending_ref_count, release_pending_refs, set_drop_policy, DropPolicy,
+};
+
+import_class! {
+    "java/lang/Integer";
+    Integer;
+    static fn valueOf(i: jint) -> Integer;
+}
+
+fn drop_on_unattached_thread(objects: Vec<Integer>) {
+    thread::spawn(move || {
+        assert!(vm().get_env().is_err());
+        drop(objects);
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+fn references_dropped_on_unattached_threads_are_queued() {
+    let mut env = vm().attach_current_thread().unwrap();
+    set_drop_policy(DropPolicy::Deferred { batch_size: 4 });
+
+    let objects: Vec<_> = (0..3)
+        .map(|i| Integer::value_of(&mut env, i).unwrap())
+        .collect();
+    drop_on_unattached_thread(objects);
+    assert_eq!(pending_ref_count(), 3);
+
+    release_pending_refs(&env);
+    assert_eq!(pending_ref_count(), 0);
+
+    // the dropping thread releases the queue once it reaches the batch size
+    let objects: Vec<_> = (0..5)
+        .map(|i| Integer::value_of(&mut env, i).unwrap())
+        .collect();
+    drop_on_unattached_thread(objects);
+    assert_eq!(pending_ref_count(), 1);
+
+    // and the next binding call on an attached thread releases the rest
+    let value = Integer::value_of(&mut env, 7).unwrap();
+    assert_eq!(pending_ref_count(), 0);
+
+    set_drop_policy(DropPolicy::Immediate);
+    drop_on_unattached_thread(vec![value]);
+    assert_eq!(pending_ref_count(), 0);
+}
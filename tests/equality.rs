@@ -0,0 +1,25 @@
+//! runs against a jvm started by the test, requires a JDK to be installed.
+mod common;
+
+use common::vm;
+use jni_bind::{import_class, jint};
+
+import_class! {
+    "java/lang/Integer";
+    Integer;
+    equality java;
+    static fn valueOf(i: jint) -> Integer;
+}
+
+#[test]
+fn java_equality_is_reflexive_after_shutdown() {
+    let mut env = vm().attach_current_thread().unwrap();
+
+    let a = Integer::value_of(&mut env, 1000).unwrap();
+    let b = Integer::value_of(&mut env, 1000).unwrap();
+    assert!(a == b);
+
+    jni_bind::shutdown();
+
+    assert!(a == a);
+}
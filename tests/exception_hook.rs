@@ -0,0 +1,62 @@
+//! runs against a jvm started by the test, requires a JDK to be installed.
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use common::vm;
+use jni_bind::{
+    import_class, jint, set_exception_hook, with_local_frame_returning, Error, ExceptionInfo,
+};
+
+import_class! {
+    "java/lang/String";
+    JavaString;
+}
+
+import_class! {
+    "java/lang/Integer";
+    Integer;
+    constructor(s: JavaString);
+    static fn parseInt(s: JavaString) -> jint;
+}
+
+static PARSE_INT: AtomicUsize = AtomicUsize::new(0);
+static CONSTRUCTOR: AtomicUsize = AtomicUsize::new(0);
+
+fn count(info: &ExceptionInfo) {
+    if info.binding != "Integer" || info.exception.class != "java.lang.NumberFormatException" {
+        return;
+    }
+
+    match info.member {
+        "parseInt" => PARSE_INT.fetch_add(1, Ordering::Relaxed),
+        "<init>" => CONSTRUCTOR.fetch_add(1, Ordering::Relaxed),
+        _ => 0,
+    };
+}
+
+fn string(env: &mut jni_bind::JNIEnv, s: &str) -> JavaString {
+    with_local_frame_returning(env, 1, |env| Ok::<_, Error>(env.new_string(s)?.into())).unwrap()
+}
+
+#[test]
+fn hook_counts_the_exceptions_of_each_member() {
+    let mut env = vm().attach_current_thread().unwrap();
+    set_exception_hook(count);
+
+    let number = string(&mut env, "12");
+    let garbage = string(&mut env, "twelve");
+
+    assert_eq!(Integer::parse_int(&mut env, &number).unwrap(), 12);
+    for _ in 0..3 {
+        assert!(matches!(
+            Integer::parse_int(&mut env, &garbage),
+            Err(Error::JavaException(_))
+        ));
+    }
+    assert!(Integer::new(&mut env, &garbage).is_err());
+
+    assert_eq!(PARSE_INT.load(Ordering::Relaxed), 3);
+    assert_eq!(CONSTRUCTOR.load(Ordering::Relaxed), 1);
+    assert!(!env.exception_check().unwrap());
+}
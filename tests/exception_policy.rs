@@ -0,0 +1,36 @@
+//! runs against a jvm started by the test, requires a JDK to be installed.
+mod common;
+
+use common::vm;
+use jni_bind::{import_class, jint, with_exception_policy, ExceptionPolicy};
+
+import_class! {
+    "java/lang/Integer";
+    Integer;
+    cache eager;
+    static fn valueOf(i: jint) -> Integer;
+    static fn noSuchMethod() -> ();
+    fn intValue(&self) -> jint;
+}
+
+#[test]
+fn eager_cache_leaves_no_exception_pending() {
+    let mut env = vm().attach_current_thread().unwrap();
+
+    let value = with_exception_policy(ExceptionPolicy::Propagate, || {
+        Integer::value_of(&mut env, 7)
+    })
+    .unwrap();
+    assert!(!env.exception_check().unwrap());
+    assert_eq!(value.int_value(&mut env).unwrap(), 7);
+
+    // the member that failed to resolve up front still reports its error when used
+    assert!(
+        with_exception_policy(ExceptionPolicy::Propagate, || Integer::no_such_method(
+            &mut env
+        ))
+        .is_err()
+    );
+    assert!(env.exception_check().unwrap());
+    env.exception_clear().unwrap();
+}
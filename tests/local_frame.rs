@@ -0,0 +1,48 @@
+//! runs against a jvm started by the test, requires a JDK to be installed.
+mod common;
+
+use common::vm;
+use jni_bind::{import_class, with_local_frame_returning, Error};
+
+import_class! {
+    "java/lang/String";
+    JavaString;
+    fn length(&self) -> jni_bind::jint;
+}
+
+import_class! {
+    "java/lang/Integer";
+    Integer;
+}
+
+#[test]
+fn returned_reference_is_promoted() {
+    let mut env = vm().attach_current_thread().unwrap();
+
+    let s: JavaString = with_local_frame_returning(&mut env, 1, |env| {
+        Ok::<_, Error>(env.new_string("abc")?.into())
+    })
+    .unwrap();
+    assert_eq!(s.length(&mut env).unwrap(), 3);
+}
+
+#[test]
+fn returned_reference_of_another_class_is_rejected() {
+    let mut env = vm().attach_current_thread().unwrap();
+
+    let r = with_local_frame_returning::<Integer, _, _>(&mut env, 1, |env| {
+        Ok::<_, Error>(env.new_string("abc")?.into())
+    });
+    match r {
+        Err(Error::WrongType {
+            expected, found, ..
+        }) => {
+            assert_eq!(expected, "java/lang/Integer");
+            assert_eq!(found, "java/lang/String");
+        }
+        r => panic!(
+            "expected a WrongType error, got {:?}",
+            r.map(|_| ()).map_err(|e| e.to_string())
+        ),
+    }
+}
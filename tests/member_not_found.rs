@@ -0,0 +1,47 @@
+//! runs against a jvm started by the test, requires a JDK to be installed.
+mod common;
+
+use common::vm;
+use jni_bind::{import_class, jlong, Error, MemberKind};
+
+import_class! {
+    "java/lang/Integer";
+    Integer;
+    // declared with the wrong parameter type, `Integer.valueOf` takes an `int` or a `String`
+    static fn valueOf(i: jlong) -> Integer;
+}
+
+#[test]
+fn wrong_descriptors_list_the_real_overloads() {
+    let mut env = vm().attach_current_thread().unwrap();
+
+    match Integer::value_of(&mut env, 7) {
+        Err(Error::MemberNotFound {
+            class,
+            kind,
+            name,
+            descriptor,
+            found,
+        }) => {
+            assert_eq!(class, "java/lang/Integer");
+            assert_eq!(kind, MemberKind::StaticMethod);
+            assert_eq!(name, "valueOf");
+            assert_eq!(descriptor, "(J)Ljava/lang/Integer;");
+            let mut overloads: Vec<_> = found.iter().map(|m| m.descriptor.as_str()).collect();
+            overloads.sort_unstable();
+            assert_eq!(
+                overloads,
+                [
+                    "(I)Ljava/lang/Integer;",
+                    "(Ljava/lang/String;)Ljava/lang/Integer;",
+                    "(Ljava/lang/String;I)Ljava/lang/Integer;"
+                ]
+            );
+            assert!(found
+                .iter()
+                .all(|m| m.is_static && m.class == "java/lang/Integer"));
+        }
+        r => panic!("expected a missing member, got {:?}", r.map(|_| ())),
+    }
+    assert!(!env.exception_check().unwrap());
+}
@@ -0,0 +1,37 @@
+//! runs against a jvm started by the test, requires a JDK to be installed.
+#![cfg(feature = "ref-tracking")]
+
+mod common;
+
+use common::vm;
+use jni_bind::ref_tracking::live_ref_count;
+use jni_bind::{import_class, jint, JObjectType};
+
+import_class! {
+    "java/lang/Integer";
+    Integer;
+    static fn valueOf(i: jint) -> Integer;
+}
+
+#[test]
+fn counts_global_references_not_clones() {
+    let mut env = vm().attach_current_thread().unwrap();
+    let before = live_ref_count();
+
+    let values: Vec<Integer> = (0..10)
+        .map(|i| Integer::value_of(&mut env, i).unwrap())
+        .collect();
+    assert_eq!(live_ref_count(), before + 10);
+
+    let clone = values[0].clone();
+    assert_eq!(live_ref_count(), before + 10);
+
+    let copy = clone.new_ref(&env).unwrap();
+    assert_eq!(live_ref_count(), before + 11);
+
+    drop(values);
+    assert_eq!(live_ref_count(), before + 2);
+
+    drop((clone, copy));
+    assert_eq!(live_ref_count(), before);
+}
@@ -0,0 +1,112 @@
+//! runs against a jvm started by the test, requires a JDK to be installed.
+mod common;
+
+use common::vm;
+use jni_bind::{import_class, jint, JObjectType};
+
+import_class! {
+    "java/lang/Thread";
+    Thread;
+    static fn currentThread() -> Thread;
+    // not methods of `Thread`, only their names matter
+    fn preload(&self) -> ();
+    fn setClassLoader(&self, loader: Thread) -> ();
+    fn clearClassLoader(&self) -> ();
+    fn newRef(&self) -> ();
+    fn sharesRef(&self) -> ();
+    fn upcast(&self) -> ();
+    fn downcast(&self) -> ();
+    fn allocUninit(&self) -> ();
+}
+
+import_class! {
+    "java/lang/String";
+    JavaString;
+}
+
+import_class! {
+    "java/security/KeyPairGenerator";
+    KeyPairGenerator;
+    static fn getInstance(algorithm: JavaString) -> KeyPairGenerator;
+    fn initialize(&self, keysize: jint) -> ();
+}
+
+#[test]
+fn java_methods_may_be_named_initialize() {
+    let mut env = vm().attach_current_thread().unwrap();
+
+    <KeyPairGenerator as JObjectType>::initialize(&mut env).unwrap();
+
+    let algorithm: JavaString = jni_bind::with_local_frame_returning(&mut env, 1, |env| {
+        Ok::<_, jni_bind::Error>(env.new_string("RSA")?.into())
+    })
+    .unwrap();
+    let generator = KeyPairGenerator::get_instance(&mut env, &algorithm).unwrap();
+    generator.initialize(&mut env, 1024).unwrap();
+}
+
+#[test]
+fn java_methods_may_be_named_preload() {
+    let mut env = vm().attach_current_thread().unwrap();
+
+    let errors = <Thread as JObjectType>::preload(&mut env).unwrap_err();
+    assert_eq!(errors.errors.len(), 8);
+
+    let thread = Thread::current_thread(&mut env).unwrap();
+    assert!(thread.preload(&mut env).is_err());
+}
+
+#[test]
+fn java_methods_may_be_named_set_class_loader() {
+    let mut env = vm().attach_current_thread().unwrap();
+
+    let thread = Thread::current_thread(&mut env).unwrap();
+    assert!(thread.set_class_loader(&mut env, &thread).is_err());
+    assert!(thread.clear_class_loader(&mut env).is_err());
+
+    let loader = env
+        .call_static_method(
+            "java/lang/ClassLoader",
+            "getSystemClassLoader",
+            "()Ljava/lang/ClassLoader;",
+            &[],
+        )
+        .unwrap()
+        .l()
+        .unwrap();
+    <Thread as JObjectType>::set_class_loader(&env, &loader).unwrap();
+    assert!(Thread::current_thread(&mut env).is_ok());
+    <Thread as JObjectType>::clear_class_loader();
+}
+
+#[test]
+fn java_methods_may_be_named_like_reference_helpers() {
+    let mut env = vm().attach_current_thread().unwrap();
+
+    let thread = Thread::current_thread(&mut env).unwrap();
+    assert!(thread.new_ref(&mut env).is_err());
+    assert!(thread.shares_ref(&mut env).is_err());
+    assert!(thread.upcast(&mut env).is_err());
+    assert!(thread.downcast(&mut env).is_err());
+
+    let copy = JObjectType::new_ref(&thread, &env).unwrap();
+    assert!(!JObjectType::shares_ref(&thread, &copy));
+    let same: Thread = JObjectType::upcast(&thread);
+    assert!(JObjectType::shares_ref(&thread, &same));
+    assert!(JObjectType::downcast::<Thread>(&copy, &mut env)
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn java_methods_may_be_named_alloc_uninit() {
+    let mut env = vm().attach_current_thread().unwrap();
+
+    let thread = Thread::current_thread(&mut env).unwrap();
+    assert!(thread.alloc_uninit(&mut env).is_err());
+
+    let uninit = unsafe { <Thread as JObjectType>::alloc_uninit(&mut env) }.unwrap();
+    assert!(JObjectType::downcast::<Thread>(&uninit, &mut env)
+        .unwrap()
+        .is_some());
+}
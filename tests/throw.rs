@@ -0,0 +1,40 @@
+//! runs against a jvm started by the test, requires a JDK to be installed.
+mod common;
+
+use common::vm;
+use jni_bind::{import_class, Error, JThrowableType, RuntimeException};
+
+import_class! {
+    "java/lang/IllegalArgumentException";
+    IllegalArgumentException;
+    extends RuntimeException;
+}
+
+import_class! {
+    "java/lang/NumberFormatException";
+    NumberFormatException;
+    extends IllegalArgumentException;
+}
+
+#[test]
+fn subclasses_of_throwable_bindings_can_be_thrown() {
+    let mut env = vm().attach_current_thread().unwrap();
+
+    NumberFormatException::throw_new(&mut env, "not a number").unwrap();
+    assert!(env.exception_check().unwrap());
+
+    match jni_bind::export::check_exception(&mut env) {
+        Err(Error::JavaException(e)) => {
+            assert_eq!(e.class, "java.lang.NumberFormatException");
+            assert_eq!(e.message.as_deref(), Some("not a number"));
+
+            e.throwable.throw(&mut env).unwrap();
+            assert!(env.exception_check().unwrap());
+            env.exception_clear().unwrap();
+        }
+        r => panic!(
+            "expected a java exception, got {:?}",
+            r.map_err(|e| e.to_string())
+        ),
+    }
+}
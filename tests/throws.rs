@@ -0,0 +1,47 @@
+//! runs against a jvm started by the test, requires a JDK to be installed.
+mod common;
+
+use common::vm;
+use jni_bind::{import_class, jint};
+
+import_class! {
+    "java/lang/String";
+    JavaString;
+}
+
+import_class! {
+    "java/lang/NumberFormatException";
+    NumberFormatException;
+}
+
+import_class! {
+    "java/lang/Integer";
+    Integer;
+    static fn valueOf(i: jint) -> Integer;
+    static fn toHexString(i: jint) -> JavaString;
+    #[throws(NumberFormatException)]
+    static fn parseInt(s: JavaString) -> jni_bind::jint;
+    fn hashCode(&self) -> jni_bind::jint;
+}
+
+#[test]
+fn return_types_may_be_paths() {
+    let mut env = vm().attach_current_thread().unwrap();
+
+    let value = Integer::value_of(&mut env, 7).unwrap();
+    assert_eq!(value.hash_code(&mut env).unwrap(), 7);
+}
+
+#[test]
+fn declared_exceptions_become_variants() {
+    let mut env = vm().attach_current_thread().unwrap();
+
+    let hex = Integer::to_hex_string(&mut env, 16).unwrap();
+    assert_eq!(Integer::parse_int(&mut env, &hex).unwrap(), 10);
+
+    let hex = Integer::to_hex_string(&mut env, 255).unwrap();
+    match Integer::parse_int(&mut env, &hex) {
+        Err(IntegerParseIntError::NumberFormatException(_)) => {}
+        r => panic!("expected a NumberFormatException, got {:?}", r.map_err(|e| e.to_string())),
+    }
+}